@@ -0,0 +1,51 @@
+//! Print procedurally generated levels as ASCII for review.
+//!
+//! Usage: `cargo run --example generate_level -- [seed] [caves|rooms] [--ron]`
+//!
+//! With `--ron`, the level is printed in the level file format instead,
+//! so it can be saved to `assets/levels`.
+
+use bevy::{asset::ron, math::UVec2};
+use cchasers::procgen::{generate, GeneratorSettings, Layout};
+
+fn main() {
+    let mut seed = 0;
+    let mut settings = GeneratorSettings::default();
+    let mut print_ron = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "caves" => {}
+            "rooms" => {
+                settings.layout = Layout::Rooms {
+                    max_rooms: 8,
+                    min_room_size: UVec2::new(4, 3),
+                    max_room_size: UVec2::new(10, 7),
+                }
+            }
+            "--ron" => print_ron = true,
+            _ => match arg.parse() {
+                Ok(value) => seed = value,
+                Err(_) => {
+                    eprintln!("usage: generate_level [seed] [caves|rooms] [--ron]");
+                    std::process::exit(2);
+                }
+            },
+        }
+    }
+
+    let level = match generate(seed, &settings) {
+        Ok(level) => level,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
+
+    if print_ron {
+        let pretty = ron::ser::PrettyConfig::default();
+        println!("{}", ron::ser::to_string_pretty(&level, pretty).unwrap());
+    } else {
+        println!("{}", level.name);
+        print!("{}", level.to_ascii());
+    }
+}
@@ -0,0 +1,165 @@
+//! Derive macros for the game.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr, Type};
+
+/// Implement `AssetKey` for an enum of unit variants, along with `FromWorld` for its
+/// `HandleMap` and a `register` function that adds the handle map to an app.
+///
+/// The enum names the type of its assets and its section of the asset manifest,
+/// and every variant names the path of its asset, relative to the `assets` directory.
+/// A missing or empty path is a compile error. Images can also name their sampler,
+/// `nearest` or `linear`. The asset manifest can override both at runtime.
+///
+/// The generated code refers to `crate::game::assets`, where `AssetKey`, `HandleMap`
+/// and the manifest are defined, so the derive only works inside the game crate.
+///
+/// ```ignore
+/// #[derive(AssetKey, Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Deserialize)]
+/// #[asset(asset = Image, section = "images")]
+/// pub enum ImageKey {
+///     #[asset(path = "images/tileset.png", sampler = "nearest")]
+///     Tileset,
+/// }
+/// ```
+#[proc_macro_derive(AssetKey, attributes(asset))]
+pub fn derive_asset_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    asset_key(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn asset_key(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "`AssetKey` can only be derived for enums",
+        ));
+    };
+
+    let mut asset = None::<Type>;
+    let mut section = None::<LitStr>;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("asset"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("asset") {
+                asset = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("section") {
+                section = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `asset` or `section`"));
+            }
+            Ok(())
+        })?;
+    }
+    let missing = |what: &str| {
+        syn::Error::new_spanned(
+            &input.ident,
+            format!("missing `#[asset({what} = ...)]` on the enum"),
+        )
+    };
+    let asset = asset.ok_or_else(|| missing("asset"))?;
+    let section = section.ok_or_else(|| missing("section"))?;
+    let section_field = Ident::new(&section.value(), section.span());
+
+    // The generated code refers to the game's asset module.
+    let assets = quote!(crate::game::assets);
+    let manifest = quote!(#assets::manifest);
+
+    let mut variants = Vec::new();
+    let mut entries = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "asset keys can only have unit variants",
+            ));
+        }
+        let mut path = None::<LitStr>;
+        let mut sampler = quote!(Default);
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("asset"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("path") {
+                    path = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("sampler") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    sampler = match value.value().as_str() {
+                        "default" => quote!(Default),
+                        "nearest" => quote!(Nearest),
+                        "linear" => quote!(Linear),
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                value,
+                                "expected `default`, `nearest` or `linear`",
+                            ))
+                        }
+                    };
+                } else {
+                    return Err(meta.error("expected `path` or `sampler`"));
+                }
+                Ok(())
+            })?;
+        }
+        let Some(path) = path else {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "missing `#[asset(path = \"...\")]` on the variant",
+            ));
+        };
+        if path.value().trim().is_empty() {
+            return Err(syn::Error::new_spanned(path, "the asset path is empty"));
+        }
+
+        let ident = &variant.ident;
+        variants.push(quote!(Self::#ident));
+        entries.push(quote! {
+            Self::#ident => #manifest::ManifestEntry {
+                path: #path.to_string(),
+                sampler: #manifest::Sampler::#sampler,
+            }
+        });
+    }
+
+    let name = &input.ident;
+    Ok(quote! {
+        impl #assets::AssetKey for #name {
+            type Asset = #asset;
+            const SECTION: &'static str = #section;
+            const ALL: &'static [Self] = &[#(#variants),*];
+
+            fn section(manifest: &#manifest::AssetManifest) -> &#manifest::ManifestSection<Self> {
+                &manifest.#section_field
+            }
+
+            fn default_entry(self) -> #manifest::ManifestEntry {
+                match self {
+                    #(#entries),*
+                }
+            }
+        }
+
+        impl ::bevy::ecs::world::FromWorld for #assets::HandleMap<#name> {
+            fn from_world(world: &mut ::bevy::ecs::world::World) -> Self {
+                Self::from_manifest(world)
+            }
+        }
+
+        impl #name {
+            /// Add the handle map of these keys to `app`, which starts loading their assets.
+            pub fn register(app: &mut ::bevy::app::App) {
+                app.register_type::<#assets::HandleMap<Self>>();
+                app.init_resource::<#assets::HandleMap<Self>>();
+            }
+        }
+    })
+}
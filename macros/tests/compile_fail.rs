@@ -0,0 +1,6 @@
+//! Check that invalid `AssetKey` derives fail with helpful errors.
+
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
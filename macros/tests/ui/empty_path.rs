@@ -0,0 +1,12 @@
+use cchasers_macros::AssetKey;
+
+#[derive(AssetKey)]
+#[asset(asset = AudioSource, section = "sfx")]
+enum SfxKey {
+    #[asset(path = "audio/sfx/step1.ogg")]
+    Step1,
+    #[asset(path = " ")]
+    Step2,
+}
+
+fn main() {}
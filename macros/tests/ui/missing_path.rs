@@ -0,0 +1,10 @@
+use cchasers_macros::AssetKey;
+
+#[derive(AssetKey)]
+#[asset(asset = Image, section = "images")]
+enum ImageKey {
+    #[asset(sampler = "nearest")]
+    Tileset,
+}
+
+fn main() {}
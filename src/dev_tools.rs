@@ -1,10 +1,22 @@
 //! Development tools for the game. This plugin is only enabled in dev builds.
 
-use bevy::{dev_tools::states::log_transitions, prelude::*};
+use bevy::{
+    dev_tools::states::log_transitions, input::common_conditions::input_just_pressed, prelude::*,
+};
 
-use crate::screen::Screen;
+use crate::{game::level::LevelCompleted, screen::Screen};
 
 pub(super) fn plugin(app: &mut App) {
     // Print state transitions in dev builds
     app.add_systems(Update, log_transitions::<Screen>);
+
+    // Skip the current level.
+    app.add_systems(
+        Update,
+        complete_level.run_if(in_state(Screen::Playing).and_then(input_just_pressed(KeyCode::F2))),
+    );
+}
+
+fn complete_level(mut commands: Commands) {
+    commands.trigger(LevelCompleted { score: 0 });
 }
@@ -0,0 +1,43 @@
+//! Undo and redo for the level editor.
+//! Levels are small, so we snapshot the whole level before every edit
+//! instead of recording how to revert each kind of edit.
+
+use crate::game::level::LevelDef;
+
+/// Older snapshots are dropped once there are more than this many.
+const MAX_UNDO_STEPS: usize = 100;
+
+#[derive(Debug, Default)]
+pub(super) struct History {
+    undo: Vec<LevelDef>,
+    redo: Vec<LevelDef>,
+}
+
+impl History {
+    /// Remember `level` as it was before an edit.
+    pub(super) fn record(&mut self, level: &LevelDef) {
+        if self.undo.len() == MAX_UNDO_STEPS {
+            self.undo.remove(0);
+        }
+        self.undo.push(level.clone());
+        self.redo.clear();
+    }
+
+    /// Revert `level` to the last snapshot. Return `false` if there is nothing to undo.
+    pub(super) fn undo(&mut self, level: &mut LevelDef) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(level, previous));
+        true
+    }
+
+    /// Reapply the last undone edit. Return `false` if there is nothing to redo.
+    pub(super) fn redo(&mut self, level: &mut LevelDef) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(level, next));
+        true
+    }
+}
@@ -0,0 +1,301 @@
+//! Mouse and keyboard controls of the level editor.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, window::PrimaryWindow};
+
+use super::{view::EditorTilemap, EditorSession, LevelObject, Placeable};
+use crate::{
+    game::{
+        camera::CameraFollow,
+        level::{CheckpointDef, EnemySpawnerDef, PickupDef, PickupKind},
+        tilemap::{TileId, Tilemap},
+    },
+    screen::Screen,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(EditorTool, EditorCursor)>();
+    app.init_resource::<EditorTool>();
+    app.init_resource::<EditorCursor>();
+
+    app.add_systems(
+        Update,
+        (
+            (update_cursor, select_tool, pan_camera).in_set(AppSet::RecordInput),
+            (
+                undo.run_if(shortcut(KeyCode::KeyZ).and_then(not(shift_pressed))),
+                redo.run_if(
+                    shortcut(KeyCode::KeyY)
+                        .or_else(shortcut(KeyCode::KeyZ).and_then(shift_pressed)),
+                ),
+                use_tool,
+                delete_object.run_if(input_just_pressed(MouseButton::Right)),
+            )
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Editor).and_then(resource_exists::<EditorSession>)),
+    );
+}
+
+/// What the left mouse button does.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub(super) enum EditorTool {
+    /// Paint tiles by dragging.
+    Paint(TileId),
+    /// Place an object on the clicked tile.
+    Place(Placeable),
+    /// Drag objects to another tile.
+    Move,
+}
+
+impl Default for EditorTool {
+    fn default() -> Self {
+        Self::Paint(TileId::FLOOR)
+    }
+}
+
+/// The tile under the mouse cursor, and the state of the current mouse stroke.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(super) struct EditorCursor {
+    pub(super) tile: Option<UVec2>,
+    /// The object being dragged by [`EditorTool::Move`], and the tile it was picked up from.
+    #[reflect(ignore)]
+    dragging: Option<(LevelObject, UVec2)>,
+    /// Whether the current stroke has already recorded an undo step.
+    stroke_recorded: bool,
+}
+
+/// Run condition for a keyboard shortcut with Ctrl held down.
+pub(super) fn shortcut(key: KeyCode) -> impl FnMut(Res<ButtonInput<KeyCode>>) -> bool + Clone {
+    move |input: Res<ButtonInput<KeyCode>>| ctrl_pressed(&input) && input.just_pressed(key)
+}
+
+fn ctrl_pressed(input: &ButtonInput<KeyCode>) -> bool {
+    input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+}
+
+fn shift_pressed(input: Res<ButtonInput<KeyCode>>) -> bool {
+    input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+fn update_cursor(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraFollow>>,
+    tilemap_query: Query<(&Tilemap, &GlobalTransform), With<EditorTilemap>>,
+    mut cursor: ResMut<EditorCursor>,
+) {
+    let world = window_query
+        .get_single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .zip(camera_query.get_single().ok())
+        .and_then(|(position, (camera, transform))| {
+            // The viewport doesn't cover the whole window when it is letterboxed.
+            let viewport = camera.logical_viewport_rect()?;
+            camera.viewport_to_world_2d(transform, position - viewport.min)
+        });
+    let tile = world.and_then(|world| {
+        tilemap_query
+            .iter()
+            .find_map(|(tilemap, transform)| tilemap.world_to_tile(transform, world))
+    });
+    if cursor.tile != tile {
+        cursor.tile = tile;
+    }
+}
+
+/// Keys that select painting each tile type, in the order of [`TileId::all`].
+/// Further conveyor directions are reached by rotating with R.
+const TILE_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+];
+
+fn select_tool(input: Res<ButtonInput<KeyCode>>, mut tool: ResMut<EditorTool>) {
+    // Leave Ctrl shortcuts like Ctrl+S alone.
+    if ctrl_pressed(&input) {
+        return;
+    }
+    let paint = TILE_KEYS
+        .iter()
+        .zip(TileId::all())
+        .find(|(key, _)| input.just_pressed(**key))
+        .map(|(_, tile)| EditorTool::Paint(tile));
+    let selected = paint.or_else(|| {
+        input.get_just_pressed().find_map(|key| match key {
+            KeyCode::KeyP => Some(EditorTool::Place(Placeable::PlayerSpawn)),
+            KeyCode::KeyS => Some(EditorTool::Place(Placeable::EnemySpawner)),
+            KeyCode::KeyG => Some(EditorTool::Place(Placeable::Pickup(PickupKind::Egg))),
+            KeyCode::KeyK => Some(EditorTool::Place(Placeable::Pickup(PickupKind::Key))),
+            KeyCode::KeyC => Some(EditorTool::Place(Placeable::Checkpoint)),
+            KeyCode::KeyX => Some(EditorTool::Place(Placeable::Exit)),
+            KeyCode::KeyB => Some(EditorTool::Place(Placeable::Boss)),
+            KeyCode::KeyM => Some(EditorTool::Move),
+            KeyCode::KeyR => match *tool {
+                EditorTool::Paint(tile) => rotate_conveyor(tile).map(EditorTool::Paint),
+                _ => None,
+            },
+            _ => None,
+        })
+    });
+    if let Some(selected) = selected {
+        *tool = selected;
+    }
+}
+
+/// The conveyor pointing clockwise of `tile`, if `tile` is a conveyor.
+fn rotate_conveyor(tile: TileId) -> Option<TileId> {
+    let conveyors = TileId::CONVEYORS;
+    let index = conveyors.iter().position(|conveyor| *conveyor == tile)?;
+    Some(conveyors[(index + 1) % conveyors.len()])
+}
+
+/// Camera speed in pixels per second.
+const PAN_SPEED: f32 = 800.0;
+
+fn pan_camera(
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut camera_query: Query<&mut Transform, With<CameraFollow>>,
+) {
+    let mut direction = Vec2::ZERO;
+    if input.pressed(KeyCode::ArrowUp) {
+        direction.y += 1.0;
+    }
+    if input.pressed(KeyCode::ArrowDown) {
+        direction.y -= 1.0;
+    }
+    if input.pressed(KeyCode::ArrowLeft) {
+        direction.x -= 1.0;
+    }
+    if input.pressed(KeyCode::ArrowRight) {
+        direction.x += 1.0;
+    }
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let offset = direction.normalize() * PAN_SPEED * time.delta_seconds();
+    for mut transform in &mut camera_query {
+        transform.translation += offset.extend(0.0);
+    }
+}
+
+fn undo(mut session: ResMut<EditorSession>) {
+    let session = &mut *session;
+    session.history.undo(&mut session.level);
+}
+
+fn redo(mut session: ResMut<EditorSession>) {
+    let session = &mut *session;
+    session.history.redo(&mut session.level);
+}
+
+fn use_tool(
+    mouse: Res<ButtonInput<MouseButton>>,
+    tool: Res<EditorTool>,
+    mut cursor: ResMut<EditorCursor>,
+    mut session: ResMut<EditorSession>,
+) {
+    let pressed = mouse.just_pressed(MouseButton::Left);
+    if pressed {
+        cursor.stroke_recorded = false;
+    }
+    let Some(tile) = cursor.tile else {
+        return;
+    };
+    match *tool {
+        EditorTool::Paint(tile_id) => {
+            if mouse.pressed(MouseButton::Left) && session.level.tile(tile) != tile_id {
+                // Record a single undo step for each stroke, once it changes a tile.
+                let level = if std::mem::replace(&mut cursor.stroke_recorded, true) {
+                    &mut session.level
+                } else {
+                    session.edit()
+                };
+                level.set_tile(tile, tile_id);
+            }
+        }
+        EditorTool::Place(placeable) if pressed => place(&mut session, placeable, tile),
+        EditorTool::Place(_) => {}
+        EditorTool::Move => {
+            if pressed {
+                cursor.dragging =
+                    LevelObject::at(&session.level, tile).map(|object| (object, tile));
+            } else if mouse.just_released(MouseButton::Left) {
+                let Some((object, from)) = cursor.dragging.take() else {
+                    return;
+                };
+                // Undo, redo or deleting during the drag can remove the object or
+                // shift the indices of others, so drop the drag unless it is still there.
+                if from != tile && LevelObject::at(&session.level, from) == Some(object) {
+                    object.move_to(session.edit(), tile);
+                }
+            }
+        }
+    }
+}
+
+/// Interval of newly placed enemy spawners.
+const DEFAULT_SPAWNER_INTERVAL_SECS: f32 = 5.0;
+
+fn place(session: &mut EditorSession, placeable: Placeable, tile: UVec2) {
+    let level = &session.level;
+    let occupied = match placeable {
+        Placeable::PlayerSpawn => level.player_spawn == tile,
+        Placeable::EnemySpawner => level
+            .enemy_spawners
+            .iter()
+            .any(|spawner| spawner.position == tile),
+        Placeable::Pickup(_) => level.pickups.iter().any(|pickup| pickup.position == tile),
+        Placeable::Checkpoint => level
+            .checkpoints
+            .iter()
+            .any(|checkpoint| checkpoint.position == tile),
+        Placeable::Exit => level.exit == Some(tile),
+        Placeable::Boss => level.boss == Some(tile),
+    };
+    if occupied {
+        return;
+    }
+
+    let level = session.edit();
+    match placeable {
+        Placeable::PlayerSpawn => level.player_spawn = tile,
+        Placeable::EnemySpawner => level.enemy_spawners.push(EnemySpawnerDef {
+            position: tile,
+            interval_secs: DEFAULT_SPAWNER_INTERVAL_SECS,
+            components: Vec::new(),
+        }),
+        Placeable::Pickup(kind) => level.pickups.push(PickupDef {
+            kind,
+            position: tile,
+            components: Vec::new(),
+        }),
+        Placeable::Checkpoint => level.checkpoints.push(CheckpointDef {
+            position: tile,
+            components: Vec::new(),
+        }),
+        Placeable::Exit => level.exit = Some(tile),
+        Placeable::Boss => level.boss = Some(tile),
+    }
+}
+
+fn delete_object(cursor: Res<EditorCursor>, mut session: ResMut<EditorSession>) {
+    let Some(tile) = cursor.tile else {
+        return;
+    };
+    match LevelObject::at(&session.level, tile) {
+        None | Some(LevelObject::PlayerSpawn) => {}
+        Some(object) => object.remove(session.edit()),
+    }
+}
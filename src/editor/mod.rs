@@ -0,0 +1,283 @@
+//! An in-game level editor. Like the dev tools, it is only compiled in dev builds.
+//!
+//! Press F3 while playing to edit the current level. In the editor, press F3 again
+//! to play the edited level starting from the tile under the cursor, and F3 once more
+//! to get back to editing. Escape leaves the editor without saving.
+
+mod history;
+mod input;
+mod view;
+
+use std::path::{Path, PathBuf};
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use self::{history::History, input::EditorCursor};
+use crate::{
+    game::{
+        camera::CameraFollow,
+        level::{CurrentLevel, LevelDef, LevelId, Levels, PickupKind},
+    },
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<EditorStatus>();
+    app.init_resource::<EditorStatus>();
+
+    app.add_systems(
+        Update,
+        open_editor.run_if(
+            in_state(Screen::Playing)
+                .or_else(in_state(Screen::Results))
+                .and_then(input_just_pressed(KeyCode::F3)),
+        ),
+    );
+    app.add_systems(
+        OnEnter(Screen::Editor),
+        (enter_editor, view::spawn_editor_view).chain(),
+    );
+    app.add_systems(OnExit(Screen::Editor), exit_editor);
+    app.add_systems(
+        Update,
+        (
+            play_from_here.run_if(input_just_pressed(KeyCode::F3)),
+            save_level.run_if(input::shortcut(KeyCode::KeyS)),
+            return_to_title_screen.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(Screen::Editor)),
+    );
+
+    app.add_plugins((input::plugin, view::plugin));
+}
+
+/// The level being edited. Kept around while play testing so edits survive it.
+#[derive(Resource, Debug)]
+struct EditorSession {
+    /// The level being edited.
+    id: LevelId,
+    /// Where the level is saved, relative to the assets folder.
+    path: Option<PathBuf>,
+    level: LevelDef,
+    history: History,
+}
+
+impl EditorSession {
+    /// Snapshot the level for undo and return it for editing.
+    fn edit(&mut self) -> &mut LevelDef {
+        self.history.record(&self.level);
+        &mut self.level
+    }
+}
+
+/// A message shown in the editor HUD, e.g. the result of saving.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct EditorStatus(String);
+
+/// Something placed on a tile of the edited level.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum LevelObject {
+    PlayerSpawn,
+    EnemySpawner(usize),
+    Pickup(usize),
+    Checkpoint(usize),
+    Exit,
+    Boss,
+}
+
+impl LevelObject {
+    /// Find the object on `tile`. If there are several, the one drawn on top is returned.
+    fn at(level: &LevelDef, tile: UVec2) -> Option<Self> {
+        if level.player_spawn == tile {
+            return Some(Self::PlayerSpawn);
+        }
+        if level.boss == Some(tile) {
+            return Some(Self::Boss);
+        }
+        if level.exit == Some(tile) {
+            return Some(Self::Exit);
+        }
+        level
+            .enemy_spawners
+            .iter()
+            .position(|spawner| spawner.position == tile)
+            .map(Self::EnemySpawner)
+            .or_else(|| {
+                level
+                    .pickups
+                    .iter()
+                    .position(|pickup| pickup.position == tile)
+                    .map(Self::Pickup)
+            })
+            .or_else(|| {
+                level
+                    .checkpoints
+                    .iter()
+                    .position(|checkpoint| checkpoint.position == tile)
+                    .map(Self::Checkpoint)
+            })
+    }
+
+    fn move_to(self, level: &mut LevelDef, tile: UVec2) {
+        match self {
+            Self::PlayerSpawn => level.player_spawn = tile,
+            Self::EnemySpawner(index) => level.enemy_spawners[index].position = tile,
+            Self::Pickup(index) => level.pickups[index].position = tile,
+            Self::Checkpoint(index) => level.checkpoints[index].position = tile,
+            Self::Exit => level.exit = Some(tile),
+            Self::Boss => level.boss = Some(tile),
+        }
+    }
+
+    /// Remove the object from the level. The player spawn can only be moved, not removed.
+    fn remove(self, level: &mut LevelDef) {
+        match self {
+            Self::PlayerSpawn => {}
+            Self::EnemySpawner(index) => {
+                level.enemy_spawners.remove(index);
+            }
+            Self::Pickup(index) => {
+                level.pickups.remove(index);
+            }
+            Self::Checkpoint(index) => {
+                level.checkpoints.remove(index);
+            }
+            Self::Exit => {
+                level.exit = None;
+                level.components.exit.clear();
+            }
+            Self::Boss => {
+                level.boss = None;
+                level.components.boss.clear();
+            }
+        }
+    }
+}
+
+/// Kinds of objects that can be placed with the mouse.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Reflect)]
+enum Placeable {
+    PlayerSpawn,
+    EnemySpawner,
+    Pickup(PickupKind),
+    Checkpoint,
+    Exit,
+    Boss,
+}
+
+fn open_editor(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Editor);
+}
+
+/// Start editing the current level, or resume editing after a play test.
+fn enter_editor(
+    mut commands: Commands,
+    session: Option<Res<EditorSession>>,
+    current_level: Res<CurrentLevel>,
+    levels: Res<Levels>,
+    level_defs: Res<Assets<LevelDef>>,
+    asset_server: Res<AssetServer>,
+) {
+    let id = &current_level.0;
+    if let Some(session) = session {
+        if id.is_playtest() || session.id == *id {
+            commands.insert_resource(CurrentLevel(session.id.clone()));
+            return;
+        }
+    }
+
+    let Some(handle) = levels.get(id) else {
+        error!("Tried to edit level {:?}, which is not loaded", id.0);
+        return;
+    };
+    let Some(level) = level_defs.get(handle) else {
+        error!("Tried to edit level {:?}, which is not loaded", id.0);
+        return;
+    };
+    commands.insert_resource(EditorSession {
+        id: id.clone(),
+        path: asset_server
+            .get_path(handle)
+            .map(|path| path.path().to_path_buf()),
+        level: level.clone(),
+        history: History::default(),
+    });
+}
+
+fn exit_editor(mut camera_query: Query<&mut Transform, With<CameraFollow>>) {
+    for mut transform in &mut camera_query {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+    }
+}
+
+/// Leave without saving, dropping the edits and their history.
+fn return_to_title_screen(mut commands: Commands, mut next_screen: ResMut<NextState<Screen>>) {
+    commands.remove_resource::<EditorSession>();
+    next_screen.set(Screen::Title);
+}
+
+/// Play the edited level, starting from the tile under the cursor if the player can stand on it.
+fn play_from_here(
+    mut commands: Commands,
+    session: Res<EditorSession>,
+    cursor: Res<EditorCursor>,
+    mut levels: ResMut<Levels>,
+    mut level_defs: ResMut<Assets<LevelDef>>,
+    mut status: ResMut<EditorStatus>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let mut level = session.level.clone();
+    if let Some(tile) = cursor.tile {
+        let properties = level.tile(tile).properties();
+        if properties.atlas_index.is_some() && !properties.solid && properties.hazard.is_none() {
+            level.player_spawn = tile;
+        }
+    }
+    if let Err(error) = level.validate() {
+        status.0 = format!("Cannot play: {error}");
+        return;
+    }
+
+    let id = LevelId(LevelId::PLAYTEST.to_string());
+    levels.insert(id.clone(), level_defs.add(level));
+    commands.insert_resource(CurrentLevel(id));
+    next_screen.set(Screen::Playing);
+}
+
+/// Write the edited level back to its file.
+fn save_level(session: Res<EditorSession>, mut status: ResMut<EditorStatus>) {
+    let Some(path) = &session.path else {
+        status.0 = "Cannot save: the level was not loaded from a file".to_string();
+        return;
+    };
+    // Levels loaded from Tiled maps are saved by Tiled.
+    if !path.to_string_lossy().ends_with(".level.ron") {
+        status.0 = format!("Cannot save: {} is not a level file", path.display());
+        return;
+    }
+    if let Err(error) = session.level.validate() {
+        status.0 = format!("Cannot save: {error}");
+        return;
+    }
+    status.0 = match write_level(path, &session.level) {
+        Ok(()) => format!("Saved {}", path.display()),
+        Err(error) => format!("Could not save {}: {error}", path.display()),
+    };
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_level(path: &Path, level: &LevelDef) -> Result<(), String> {
+    use bevy::asset::{io::file::FileAssetReader, ron};
+
+    let contents =
+        ron::ser::to_string_pretty(level, default()).map_err(|error| error.to_string())?;
+    let path = FileAssetReader::get_base_path().join("assets").join(path);
+    std::fs::write(path, contents).map_err(|error| error.to_string())
+}
+
+#[cfg(target_family = "wasm")]
+fn write_level(_path: &Path, _level: &LevelDef) -> Result<(), String> {
+    Err("saving is not supported on the web".to_string())
+}
@@ -0,0 +1,242 @@
+//! Draw the edited level, the objects placed on it and the editor HUD.
+
+use bevy::prelude::*;
+
+use super::{
+    input::{EditorCursor, EditorTool},
+    EditorSession, EditorStatus, Placeable,
+};
+use crate::{
+    game::{layers::DrawLayer, level::LevelDef, tilemap::Tilemap},
+    screen::Screen,
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(EditorTilemap, EditorMarker, CursorHighlight, EditorHud)>();
+    app.add_systems(
+        Update,
+        (
+            sync_level.run_if(resource_exists_and_changed::<EditorSession>),
+            update_cursor_highlight,
+            update_hud,
+        )
+            .run_if(in_state(Screen::Editor).and_then(resource_exists::<EditorSession>)),
+    );
+}
+
+/// The tilemap showing the edited level's tiles.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(super) struct EditorTilemap;
+
+/// A sprite showing an object placed on the edited level.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct EditorMarker;
+
+/// Highlights the tile under the mouse cursor.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CursorHighlight;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct EditorHud;
+
+/// Draw markers and the cursor highlight above the tilemap and level entities.
+const MARKER_Z: f32 = 10.0;
+const CURSOR_Z: f32 = 11.0;
+
+pub(super) fn spawn_editor_view(mut commands: Commands, session: Option<Res<EditorSession>>) {
+    let Some(session) = session else {
+        return;
+    };
+    let level = &session.level;
+    commands.spawn((
+        Name::new("Editor Tilemap"),
+        EditorTilemap,
+        level.to_tilemap(),
+        SpatialBundle::from_transform(Transform::from_translation(
+            level.tilemap_origin().extend(0.0),
+        )),
+        DrawLayer::Ground,
+        StateScoped(Screen::Editor),
+    ));
+
+    commands.spawn((
+        Name::new("Cursor Highlight"),
+        CursorHighlight,
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgba(1.0, 1.0, 1.0, 0.3),
+                custom_size: Some(Vec2::splat(level.tile_size)),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        StateScoped(Screen::Editor),
+    ));
+
+    commands.spawn((
+        Name::new("Editor HUD"),
+        EditorHud,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: ui_palette::LABEL_TEXT,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        }),
+        StateScoped(Screen::Editor),
+    ));
+}
+
+/// Update the tilemap and respawn the object markers after the level was edited.
+fn sync_level(
+    mut commands: Commands,
+    session: Res<EditorSession>,
+    mut tilemap_query: Query<&mut Tilemap, With<EditorTilemap>>,
+    marker_query: Query<Entity, With<EditorMarker>>,
+) {
+    let level = &session.level;
+    let edited = level.to_tilemap();
+    for mut tilemap in &mut tilemap_query {
+        for (position, tile) in edited.iter() {
+            if tilemap.get(position) != tile {
+                tilemap.set(position, tile);
+            }
+        }
+    }
+
+    for entity in &marker_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    let markers = std::iter::once((level.player_spawn, Placeable::PlayerSpawn))
+        .chain(
+            level
+                .enemy_spawners
+                .iter()
+                .map(|spawner| (spawner.position, Placeable::EnemySpawner)),
+        )
+        .chain(
+            level
+                .pickups
+                .iter()
+                .map(|pickup| (pickup.position, Placeable::Pickup(pickup.kind))),
+        )
+        .chain(
+            level
+                .checkpoints
+                .iter()
+                .map(|checkpoint| (checkpoint.position, Placeable::Checkpoint)),
+        )
+        .chain(level.exit.map(|exit| (exit, Placeable::Exit)))
+        .chain(level.boss.map(|boss| (boss, Placeable::Boss)));
+    for (position, placeable) in markers {
+        spawn_marker(&mut commands, level, position, placeable);
+    }
+}
+
+fn spawn_marker(commands: &mut Commands, level: &LevelDef, position: UVec2, placeable: Placeable) {
+    let (label, color) = match placeable {
+        Placeable::PlayerSpawn => ("P", Color::srgb(0.361, 0.780, 0.431)),
+        Placeable::EnemySpawner => ("E", Color::srgb(0.780, 0.361, 0.361)),
+        Placeable::Pickup(_) => ("*", Color::srgb(0.878, 0.780, 0.329)),
+        Placeable::Checkpoint => ("C", Color::srgb(0.361, 0.631, 0.780)),
+        Placeable::Exit => ("X", Color::srgb(0.925, 0.925, 0.925)),
+        Placeable::Boss => ("B", Color::srgb(0.584, 0.361, 0.780)),
+    };
+    commands
+        .spawn((
+            Name::new("Editor Marker"),
+            EditorMarker,
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(level.tile_size / 2.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(
+                    level.tile_to_world(position).extend(MARKER_Z),
+                ),
+                ..default()
+            },
+            StateScoped(Screen::Editor),
+        ))
+        .with_children(|children| {
+            children.spawn(Text2dBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::BLACK,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                ..default()
+            });
+        });
+}
+
+fn update_cursor_highlight(
+    cursor: Res<EditorCursor>,
+    session: Res<EditorSession>,
+    mut highlight_query: Query<(&mut Transform, &mut Visibility), With<CursorHighlight>>,
+) {
+    for (mut transform, mut visibility) in &mut highlight_query {
+        match cursor.tile {
+            Some(tile) => {
+                transform.translation = session.level.tile_to_world(tile).extend(CURSOR_Z);
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+const HELP: &str =
+    "0-8: paint tiles  R: rotate conveyor  P: player  S: spawner  G: egg  K: key  C: checkpoint  X: exit  B: boss  M: move
+Left click: use tool  Right click: delete  Arrows: pan
+Ctrl+Z: undo  Ctrl+Y: redo  Ctrl+S: save  F3: play from here  Esc: quit";
+
+fn update_hud(
+    tool: Res<EditorTool>,
+    status: Res<EditorStatus>,
+    cursor: Res<EditorCursor>,
+    session: Res<EditorSession>,
+    mut hud_query: Query<&mut Text, With<EditorHud>>,
+) {
+    let tool_name = match *tool {
+        EditorTool::Paint(tile) => format!("Paint {}", tile.properties().name),
+        EditorTool::Place(Placeable::PlayerSpawn) => "Place player spawn".to_string(),
+        EditorTool::Place(Placeable::EnemySpawner) => "Place enemy spawner".to_string(),
+        EditorTool::Place(Placeable::Pickup(kind)) => format!("Place {kind:?}"),
+        EditorTool::Place(Placeable::Checkpoint) => "Place checkpoint".to_string(),
+        EditorTool::Place(Placeable::Exit) => "Place exit".to_string(),
+        EditorTool::Place(Placeable::Boss) => "Place boss".to_string(),
+        EditorTool::Move => "Move".to_string(),
+    };
+    let tile = cursor
+        .tile
+        .map_or(String::new(), |tile| format!(" at {}, {}", tile.x, tile.y));
+    let value = format!(
+        "Editing {}{tile}\nTool: {tool_name}\n{HELP}\n{}",
+        session.level.name, status.0
+    );
+
+    for mut text in &mut hud_query {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
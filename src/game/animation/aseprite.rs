@@ -0,0 +1,313 @@
+//! Load [`SpriteSheet`]s exported by [Aseprite](https://www.aseprite.org/) with
+//! `File > Export Sprite Sheet`, saved as `.aseprite.json` next to the sheet image.
+//!
+//! - The JSON data has to be exported as an array, with tags included.
+//! - Every tag becomes a clip of the sheet's [`AnimationLibrary`], named after the tag.
+//!   Tags played forward loop, unless they repeat only once. Ping-pong tags play back and forth.
+//! - The user data of a tag can tag its frames with [`AnimationEvent`](super::AnimationEvent)s,
+//!   in RON syntax by the frame's index within the tag, e.g. `{2: ["footstep"]}`.
+
+use std::{error::Error, fmt, io};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext, ParseAssetPathError},
+    prelude::*,
+    render::texture::{ImageLoaderSettings, ImageSampler},
+    utils::HashMap,
+};
+use serde::Deserialize;
+
+use super::library::{AnimationLibrary, AnimationLibraryError, PlaybackMode, SpriteClipFile};
+
+/// A sprite sheet image with its atlas layout and animation clips.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct SpriteSheet {
+    #[dependency]
+    pub image: Handle<Image>,
+    #[dependency]
+    pub layout: Handle<TextureAtlasLayout>,
+    #[dependency]
+    pub animations: Handle<AnimationLibrary>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFile {
+    frames: Vec<AsepriteFrame>,
+    meta: AsepriteMeta,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    frame: AsepriteRect,
+    /// Milliseconds the frame is shown.
+    duration: u64,
+}
+
+#[derive(Deserialize)]
+struct AsepriteRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsepriteMeta {
+    /// Path of the sheet image, relative to the JSON file.
+    image: String,
+    size: AsepriteSize,
+    #[serde(default)]
+    frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    direction: String,
+    /// How often the tag is played, missing if it repeats forever.
+    #[serde(default)]
+    repeat: Option<String>,
+    /// User data of the tag.
+    #[serde(default)]
+    data: Option<String>,
+}
+
+impl AsepriteTag {
+    fn into_clip_file(self, frames: &[AsepriteFrame]) -> Result<SpriteClipFile, AsepriteError> {
+        let Some(tag_frames) = frames.get(self.from..=self.to) else {
+            return Err(AsepriteError::TagOutOfRange(self.name));
+        };
+        let mode = match (self.direction.as_str(), self.repeat.as_deref()) {
+            ("forward", Some("1")) => PlaybackMode::Once,
+            ("forward", _) => PlaybackMode::Loop,
+            ("pingpong", _) => PlaybackMode::PingPong,
+            _ => {
+                return Err(AsepriteError::UnsupportedDirection {
+                    tag: self.name,
+                    direction: self.direction,
+                })
+            }
+        };
+        let events = match self.data.as_deref().map(str::trim) {
+            None | Some("") => HashMap::default(),
+            Some(data) => {
+                ron::de::from_str(data).map_err(|error| AsepriteError::InvalidEvents {
+                    tag: self.name.clone(),
+                    error,
+                })?
+            }
+        };
+        Ok(SpriteClipFile {
+            frames: (self.from, self.to),
+            durations_ms: tag_frames.iter().map(|frame| frame.duration).collect(),
+            mode,
+            events,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = SpriteSheet;
+    type Settings = ();
+    type Error = AsepriteError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file = serde_json::from_slice::<AsepriteFile>(&bytes)?;
+
+        let mut layout =
+            TextureAtlasLayout::new_empty(UVec2::new(file.meta.size.w, file.meta.size.h));
+        for AsepriteFrame { frame, .. } in &file.frames {
+            let min = UVec2::new(frame.x, frame.y);
+            layout.add_texture(URect::from_corners(min, min + UVec2::new(frame.w, frame.h)));
+        }
+
+        let mut clips = HashMap::default();
+        for tag in file.meta.frame_tags {
+            let name = tag.name.clone();
+            let clip = tag.into_clip_file(&file.frames)?.into_clip(&name)?;
+            clips.insert(name, clip);
+        }
+
+        let image_path = load_context.asset_path().resolve_embed(&file.meta.image)?;
+        let image = load_context
+            .loader()
+            .with_settings(|settings: &mut ImageLoaderSettings| {
+                settings.sampler = ImageSampler::nearest();
+            })
+            .load(image_path);
+        Ok(SpriteSheet {
+            image,
+            layout: load_context.add_labeled_asset("layout".to_string(), layout),
+            animations: load_context
+                .add_labeled_asset("animations".to_string(), AnimationLibrary { clips }),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
+
+#[derive(Debug)]
+pub enum AsepriteError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    InvalidImagePath(ParseAssetPathError),
+    /// A tag covers frames the sheet doesn't have.
+    TagOutOfRange(String),
+    /// A tag plays in reverse, which clips don't support.
+    UnsupportedDirection {
+        tag: String,
+        direction: String,
+    },
+    /// The user data of a tag isn't a map of frame events.
+    InvalidEvents {
+        tag: String,
+        error: ron::error::SpannedError,
+    },
+    InvalidClip(AnimationLibraryError),
+}
+
+impl fmt::Display for AsepriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read file: {error}"),
+            Self::Json(error) => write!(f, "could not parse file: {error}"),
+            Self::InvalidImagePath(error) => write!(f, "invalid image path: {error}"),
+            Self::TagOutOfRange(tag) => write!(f, "tag {tag:?} covers frames that don't exist"),
+            Self::UnsupportedDirection { tag, direction } => {
+                write!(f, "tag {tag:?} has unsupported direction {direction:?}")
+            }
+            Self::InvalidEvents { tag, error } => {
+                write!(f, "could not parse events of tag {tag:?}: {error}")
+            }
+            Self::InvalidClip(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for AsepriteError {}
+
+impl From<io::Error> for AsepriteError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for AsepriteError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<ParseAssetPathError> for AsepriteError {
+    fn from(error: ParseAssetPathError) -> Self {
+        Self::InvalidImagePath(error)
+    }
+}
+
+impl From<AnimationLibraryError> for AsepriteError {
+    fn from(error: AnimationLibraryError) -> Self {
+        Self::InvalidClip(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(from: usize, to: usize, direction: &str, data: Option<&str>) -> AsepriteTag {
+        AsepriteTag {
+            name: "test".to_string(),
+            from,
+            to,
+            direction: direction.to_string(),
+            repeat: None,
+            data: data.map(str::to_string),
+        }
+    }
+
+    fn frames(count: usize) -> Vec<AsepriteFrame> {
+        (0..count)
+            .map(|_| AsepriteFrame {
+                frame: AsepriteRect {
+                    x: 0,
+                    y: 0,
+                    w: 32,
+                    h: 32,
+                },
+                duration: 100,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shipped_sheet_is_imported() {
+        let file = serde_json::from_str::<AsepriteFile>(include_str!(
+            "../../../assets/images/ducky.aseprite.json"
+        ))
+        .unwrap();
+        let mut clips = HashMap::default();
+        for tag in file.meta.frame_tags {
+            let name = tag.name.clone();
+            let clip = tag
+                .into_clip_file(&file.frames)
+                .unwrap()
+                .into_clip(&name)
+                .unwrap();
+            clips.insert(name, clip);
+        }
+        assert_eq!(clips["walk"].events(2), ["footstep"]);
+        assert_eq!(clips["hurt"].mode, PlaybackMode::Once);
+    }
+
+    #[test]
+    fn directions_map_to_playback_modes() {
+        let frames = frames(4);
+        let ping_pong = tag(0, 3, "pingpong", None).into_clip_file(&frames).unwrap();
+        assert_eq!(ping_pong.mode, PlaybackMode::PingPong);
+
+        let mut once = tag(1, 2, "forward", Some(r#"{0: ["dust"]}"#));
+        once.repeat = Some("1".to_string());
+        let once = once.into_clip_file(&frames).unwrap();
+        assert_eq!(once.mode, PlaybackMode::Once);
+        assert_eq!(once.frames, (1, 2));
+        assert_eq!(once.events[&0], ["dust"]);
+    }
+
+    #[test]
+    fn invalid_tags_are_rejected() {
+        let frames = frames(4);
+        assert!(matches!(
+            tag(2, 4, "forward", None).into_clip_file(&frames),
+            Err(AsepriteError::TagOutOfRange(_))
+        ));
+        assert!(matches!(
+            tag(0, 3, "reverse", None).into_clip_file(&frames),
+            Err(AsepriteError::UnsupportedDirection { .. })
+        ));
+        assert!(matches!(
+            tag(0, 3, "forward", Some("footstep")).into_clip_file(&frames),
+            Err(AsepriteError::InvalidEvents { .. })
+        ));
+    }
+}
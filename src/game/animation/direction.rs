@@ -0,0 +1,173 @@
+//! Play direction-specific variants of clips, picked from the entity's [`Facing`].
+//! A clip `walk` facing down plays `walk_down` if the library has it, see [`Compass::suffix`].
+//! Clips without variants play as they are.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use super::{library::AnimationLibrary, SpriteAnimation};
+use crate::game::movement::Facing;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<DirectionalAnimation>();
+}
+
+/// Picks the variant of the current clip that matches the entity's [`Facing`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component, Default)]
+pub struct DirectionalAnimation {
+    pub directions: Directions,
+    /// Mirror the sprite to face left when the library has no clips facing left,
+    /// using the clips facing right instead.
+    pub mirror: bool,
+    /// How far the facing has to turn past the border between two directions before
+    /// switching, in radians, so that moving along the border doesn't flicker.
+    pub hysteresis: f32,
+    direction: Option<Compass>,
+}
+
+impl Default for DirectionalAnimation {
+    fn default() -> Self {
+        Self {
+            directions: Directions::Eight,
+            mirror: true,
+            hysteresis: 10_f32.to_radians(),
+            direction: None,
+        }
+    }
+}
+
+impl DirectionalAnimation {
+    /// The direction closest to `facing`, or the previous direction if it is close enough.
+    fn pick(&self, facing: Dir2) -> Compass {
+        let count = self.directions.count();
+        let width = TAU / count as f32;
+        let angle = facing.to_angle();
+        if let Some(direction) = self.direction {
+            let index = direction as usize * count / Compass::ALL.len();
+            let is_valid = index * Compass::ALL.len() / count == direction as usize;
+            let center = index as f32 * width;
+            let distance = (angle - center + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+            if is_valid && distance.abs() <= width / 2.0 + self.hysteresis {
+                return direction;
+            }
+        }
+        let index = (angle / width).round().rem_euclid(count as f32) as usize;
+        Compass::ALL[index * Compass::ALL.len() / count]
+    }
+}
+
+/// How many directions have their own clips.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Directions {
+    /// Right, up, left and down.
+    Four,
+    /// Also the diagonals between them.
+    #[default]
+    Eight,
+}
+
+impl Directions {
+    fn count(self) -> usize {
+        match self {
+            Self::Four => 4,
+            Self::Eight => 8,
+        }
+    }
+}
+
+/// A direction a clip can face, counter-clockwise starting from the right.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compass {
+    Right,
+    UpRight,
+    Up,
+    UpLeft,
+    Left,
+    DownLeft,
+    Down,
+    DownRight,
+}
+
+impl Compass {
+    const ALL: [Self; 8] = [
+        Self::Right,
+        Self::UpRight,
+        Self::Up,
+        Self::UpLeft,
+        Self::Left,
+        Self::DownLeft,
+        Self::Down,
+        Self::DownRight,
+    ];
+
+    /// Appended to a clip's name to get its variant facing this direction.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Self::Right => "right",
+            Self::UpRight => "up_right",
+            Self::Up => "up",
+            Self::UpLeft => "up_left",
+            Self::Left => "left",
+            Self::DownLeft => "down_left",
+            Self::Down => "down",
+            Self::DownRight => "down_right",
+        }
+    }
+
+    /// The direction on the other side of the vertical axis.
+    fn mirrored(self) -> Self {
+        Self::ALL[(12 - self as usize) % 8]
+    }
+
+    /// Whether this direction points left (`Some(true)`), right (`Some(false)`) or neither.
+    fn is_left(self) -> Option<bool> {
+        match self {
+            Self::UpLeft | Self::Left | Self::DownLeft => Some(true),
+            Self::UpRight | Self::Right | Self::DownRight => Some(false),
+            Self::Up | Self::Down => None,
+        }
+    }
+}
+
+/// Switch to the variant of the current clip facing the entity's direction,
+/// mirroring the sprite where needed.
+pub(super) fn apply_directions(
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut animation_query: Query<(
+        &Facing,
+        &mut DirectionalAnimation,
+        &mut SpriteAnimation,
+        &mut Sprite,
+    )>,
+) {
+    for (facing, mut directional, mut animation, mut sprite) in &mut animation_query {
+        let Some(library) = libraries.get(&animation.library) else {
+            continue;
+        };
+        let direction = directional.pick(facing.0);
+        if directional.direction != Some(direction) {
+            directional.direction = Some(direction);
+        }
+
+        let has_variant = |direction: Compass| {
+            library
+                .clip(&format!("{}_{}", animation.clip, direction.suffix()))
+                .is_some()
+        };
+        let (variant, flip) = if has_variant(direction) {
+            (Some(direction), false)
+        } else if directional.mirror && has_variant(direction.mirrored()) {
+            (Some(direction.mirrored()), true)
+        } else if directional.mirror {
+            (None, direction.is_left().unwrap_or(sprite.flip_x))
+        } else {
+            (None, false)
+        };
+        animation.set_variant(variant.map(Compass::suffix));
+        if sprite.flip_x != flip {
+            sprite.flip_x = flip;
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Named animation clips for a texture atlas, loaded from `.anim.ron` files
+//! or imported from Aseprite, see [`super::aseprite`].
+
+use std::{error::Error, fmt, io, time::Duration};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::HashMap,
+};
+use serde::Deserialize;
+
+/// All clips of one texture atlas, by name.
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct AnimationLibrary {
+    pub clips: HashMap<String, SpriteClip>,
+}
+
+impl AnimationLibrary {
+    pub fn clip(&self, name: &str) -> Option<&SpriteClip> {
+        self.clips.get(name)
+    }
+}
+
+/// A range of frames in a texture atlas, played one after another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteClip {
+    /// Atlas index of the first frame.
+    pub first: usize,
+    /// Atlas index of the last frame.
+    pub last: usize,
+    /// How long each frame is shown, one entry per frame.
+    pub durations: Vec<Duration>,
+    pub mode: PlaybackMode,
+    /// Tags raised as [`AnimationEvent`](super::AnimationEvent)s when a frame is reached,
+    /// by the frame's index within the clip.
+    pub events: HashMap<usize, Vec<String>>,
+}
+
+impl SpriteClip {
+    /// The number of frames in the clip.
+    pub fn len(&self) -> usize {
+        self.last - self.first + 1
+    }
+
+    /// How long the `frame`th frame of the clip is shown.
+    pub fn duration(&self, frame: usize) -> Duration {
+        self.durations[frame.min(self.durations.len() - 1)]
+    }
+
+    /// The tags of the `frame`th frame of the clip.
+    pub fn events(&self, frame: usize) -> &[String] {
+        self.events.get(&frame).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// What happens when a clip reaches its last frame.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum PlaybackMode {
+    /// Start over from the first frame.
+    #[default]
+    Loop,
+    /// Stay on the last frame.
+    Once,
+    /// Play the frames backwards, then forwards again.
+    PingPong,
+}
+
+/// The on-disk representation of an [`AnimationLibrary`].
+#[derive(Deserialize)]
+struct AnimationLibraryFile {
+    clips: HashMap<String, SpriteClipFile>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SpriteClipFile {
+    /// Atlas indices of the first and last frame.
+    pub frames: (usize, usize),
+    /// Milliseconds each frame is shown. A single entry applies to every frame.
+    pub durations_ms: Vec<u64>,
+    #[serde(default)]
+    pub mode: PlaybackMode,
+    /// Tags of frames, by their index within the clip.
+    #[serde(default)]
+    pub events: HashMap<usize, Vec<String>>,
+}
+
+impl SpriteClipFile {
+    pub fn into_clip(self, name: &str) -> Result<SpriteClip, AnimationLibraryError> {
+        let (first, last) = self.frames;
+        if last < first {
+            return Err(AnimationLibraryError::EmptyClip(name.to_string()));
+        }
+        let len = last - first + 1;
+        let durations = match self.durations_ms[..] {
+            [duration] => vec![duration; len],
+            _ if self.durations_ms.len() == len => self.durations_ms,
+            _ => {
+                return Err(AnimationLibraryError::DurationCount {
+                    clip: name.to_string(),
+                    frames: len,
+                    durations: self.durations_ms.len(),
+                })
+            }
+        };
+        if durations.contains(&0) {
+            return Err(AnimationLibraryError::ZeroDuration(name.to_string()));
+        }
+        if let Some(&frame) = self.events.keys().find(|&&frame| frame >= len) {
+            return Err(AnimationLibraryError::EventOutOfRange {
+                clip: name.to_string(),
+                frame,
+            });
+        }
+        Ok(SpriteClip {
+            first,
+            last,
+            durations: durations.into_iter().map(Duration::from_millis).collect(),
+            mode: self.mode,
+            events: self.events,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct AnimationLibraryLoader;
+
+impl AssetLoader for AnimationLibraryLoader {
+    type Asset = AnimationLibrary;
+    type Settings = ();
+    type Error = AnimationLibraryError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file = ron::de::from_bytes::<AnimationLibraryFile>(&bytes)?;
+        let clips = file
+            .clips
+            .into_iter()
+            .map(|(name, clip)| clip.into_clip(&name).map(|clip| (name, clip)))
+            .collect::<Result<_, _>>()?;
+        Ok(AnimationLibrary { clips })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+#[derive(Debug)]
+pub enum AnimationLibraryError {
+    Io(io::Error),
+    Ron(ron::error::SpannedError),
+    /// A clip's last frame comes before its first frame.
+    EmptyClip(String),
+    /// A clip has neither one duration nor one per frame.
+    DurationCount {
+        clip: String,
+        frames: usize,
+        durations: usize,
+    },
+    /// A clip has a frame that is shown for no time at all.
+    ZeroDuration(String),
+    /// A clip tags a frame it doesn't have.
+    EventOutOfRange {
+        clip: String,
+        frame: usize,
+    },
+}
+
+impl fmt::Display for AnimationLibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read file: {error}"),
+            Self::Ron(error) => write!(f, "could not parse file: {error}"),
+            Self::EmptyClip(clip) => write!(f, "clip {clip:?} has no frames"),
+            Self::DurationCount {
+                clip,
+                frames,
+                durations,
+            } => write!(
+                f,
+                "clip {clip:?} has {frames} frames but {durations} durations"
+            ),
+            Self::ZeroDuration(clip) => write!(f, "clip {clip:?} has a frame with no duration"),
+            Self::EventOutOfRange { clip, frame } => {
+                write!(
+                    f,
+                    "clip {clip:?} has events on frame {frame}, which it doesn't have"
+                )
+            }
+        }
+    }
+}
+
+impl Error for AnimationLibraryError {}
+
+impl From<io::Error> for AnimationLibraryError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for AnimationLibraryError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip_file(frames: (usize, usize), durations_ms: Vec<u64>) -> SpriteClipFile {
+        SpriteClipFile {
+            frames,
+            durations_ms,
+            mode: PlaybackMode::Loop,
+            events: HashMap::default(),
+        }
+    }
+
+    #[test]
+    fn library_file_is_parsed() {
+        let file = ron::de::from_str::<AnimationLibraryFile>(
+            r#"(clips: {
+                "walk": (frames: (2, 4), durations_ms: [100], events: {1: ["footstep"]}),
+                "jump": (frames: (5, 6), durations_ms: [50, 150], mode: Once),
+            })"#,
+        )
+        .unwrap();
+        let mut clips = file.clips;
+        let walk = clips.remove("walk").unwrap().into_clip("walk").unwrap();
+        assert_eq!(walk.len(), 3);
+        assert_eq!(walk.durations, vec![Duration::from_millis(100); 3]);
+        assert_eq!(walk.events(1), ["footstep"]);
+
+        let jump = clips.remove("jump").unwrap().into_clip("jump").unwrap();
+        assert_eq!(jump.mode, PlaybackMode::Once);
+        assert_eq!(jump.duration(1), Duration::from_millis(150));
+    }
+
+    #[test]
+    fn invalid_clips_are_rejected() {
+        assert!(matches!(
+            clip_file((3, 2), vec![100]).into_clip("backwards"),
+            Err(AnimationLibraryError::EmptyClip(_))
+        ));
+        assert!(matches!(
+            clip_file((0, 2), vec![100, 100]).into_clip("short"),
+            Err(AnimationLibraryError::DurationCount {
+                frames: 3,
+                durations: 2,
+                ..
+            })
+        ));
+        assert!(matches!(
+            clip_file((0, 1), vec![100, 0]).into_clip("instant"),
+            Err(AnimationLibraryError::ZeroDuration(_))
+        ));
+
+        let mut tagged = clip_file((0, 1), vec![100]);
+        tagged.events.insert(2, vec!["footstep".to_string()]);
+        assert!(matches!(
+            tagged.into_clip("tagged"),
+            Err(AnimationLibraryError::EventOutOfRange { frame: 2, .. })
+        ));
+    }
+}
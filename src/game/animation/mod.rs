@@ -0,0 +1,400 @@
+//! Sprite animation driven by named clips from an [`AnimationLibrary`].
+//! This is based on multiple examples and may be very different for your game.
+//! - [Sprite flipping](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_flipping.rs)
+//! - [Sprite animation](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_animation.rs)
+//! - [Timers](https://github.com/bevyengine/bevy/blob/latest/examples/time/timers.rs)
+
+pub mod aseprite;
+pub mod direction;
+pub mod library;
+pub mod state;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use self::{
+    aseprite::{AsepriteLoader, SpriteSheet},
+    library::{AnimationLibrary, AnimationLibraryLoader, PlaybackMode, SpriteClip},
+    state::{
+        AnimationCondition, AnimationStateMachine, AnimationTransition, PlayOneShot, TransitionExit,
+    },
+};
+use super::{
+    audio::sfx::PlaySfx,
+    health::{Damage, Invulnerable},
+    movement::{update_facing, MovementController},
+    spawn::player::Player,
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<AnimationLibrary>();
+    app.init_asset_loader::<AnimationLibraryLoader>();
+    app.init_asset::<SpriteSheet>();
+    app.init_asset_loader::<AsepriteLoader>();
+    app.add_plugins((direction::plugin, state::plugin));
+
+    // Animate and play sound effects based on controls.
+    app.register_type::<SpriteAnimation>();
+    app.observe(play_step_sfx);
+    app.observe(play_hurt_animation);
+    app.add_systems(
+        Update,
+        (
+            advance_animations.in_set(AppSet::TickTimers),
+            (
+                update_player_animation,
+                state::update_state_machines,
+                direction::apply_directions,
+                update_animation_atlas,
+            )
+                .chain()
+                .after(update_facing)
+                .in_set(AppSet::Update),
+        ),
+    );
+}
+
+/// Triggered on an entity with a [`SpriteAnimation`] when it reaches a tagged frame,
+/// once for every tag. Frames skipped during a lag spike raise their events too.
+#[derive(Event, Debug, Clone)]
+pub struct AnimationEvent {
+    /// The tag of the frame, e.g. `footstep`.
+    pub tag: String,
+}
+
+/// Plays clips from an [`AnimationLibrary`] on the entity's [`TextureAtlas`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct SpriteAnimation {
+    pub library: Handle<AnimationLibrary>,
+    /// How fast the clip plays, 1 being its normal speed.
+    pub speed: f32,
+    clip: String,
+    /// The direction-specific variant of the clip that is playing, see [`direction`].
+    variant: Option<String>,
+    /// Index of the current frame within the clip.
+    frame: usize,
+    /// Time spent on the current frame.
+    elapsed: Duration,
+    /// Whether a [`PlaybackMode::PingPong`] clip is currently playing backwards.
+    reversed: bool,
+    /// Whether the first frame of the clip has been reached, and its events raised.
+    started: bool,
+    /// Whether a [`PlaybackMode::Once`] clip has reached its last frame.
+    finished: bool,
+}
+
+impl SpriteAnimation {
+    pub fn new(library: Handle<AnimationLibrary>, clip: impl Into<String>) -> Self {
+        Self {
+            library,
+            speed: 1.0,
+            clip: clip.into(),
+            variant: None,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            reversed: false,
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Switch to another clip, starting from its first frame.
+    /// Does nothing if the clip is already playing.
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            self.restart(clip);
+        }
+    }
+
+    /// Play a clip from its first frame, even if it is already playing.
+    pub fn restart(&mut self, clip: &str) {
+        *self = Self::new(self.library.clone(), clip).with_speed(self.speed);
+    }
+
+    pub fn with_speed(self, speed: f32) -> Self {
+        Self { speed, ..self }
+    }
+
+    /// Play the variant of the clip with this suffix, e.g. `down` for `walk_down`,
+    /// without starting over. `None` plays the clip itself.
+    pub fn set_variant(&mut self, suffix: Option<&str>) {
+        let is_playing = match (&self.variant, suffix) {
+            (Some(variant), Some(suffix)) => {
+                variant
+                    .strip_prefix(self.clip.as_str())
+                    .and_then(|rest| rest.strip_prefix('_'))
+                    == Some(suffix)
+            }
+            (variant, suffix) => variant.is_none() && suffix.is_none(),
+        };
+        if !is_playing {
+            self.variant = suffix.map(|suffix| format!("{}_{suffix}", self.clip));
+        }
+    }
+
+    /// The clip that is playing, falling back to the clip itself if the library
+    /// doesn't have the variant.
+    fn sprite_clip<'a>(&self, library: &'a AnimationLibrary) -> Option<&'a SpriteClip> {
+        self.variant
+            .as_deref()
+            .and_then(|variant| library.clip(variant))
+            .or_else(|| library.clip(&self.clip))
+    }
+
+    /// Whether a clip that plays once has reached its last frame.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Return the sprite index of the current frame in the atlas.
+    pub fn atlas_index(&self, clip: &SpriteClip) -> usize {
+        clip.first + self.frame.min(clip.len() - 1)
+    }
+
+    /// Advance the animation by `delta`, skipping frames if it is longer than a frame.
+    /// Calls `on_frame` with every frame that is reached, including skipped ones.
+    fn advance(&mut self, delta: Duration, clip: &SpriteClip, mut on_frame: impl FnMut(usize)) {
+        if !self.started {
+            self.started = true;
+            on_frame(self.frame);
+        }
+        if self.finished {
+            return;
+        }
+        self.elapsed += delta.mul_f32(self.speed.max(0.0));
+        loop {
+            let duration = clip.duration(self.frame);
+            if self.elapsed < duration {
+                break;
+            }
+            self.elapsed -= duration;
+            if !self.step(clip) {
+                self.finished = true;
+                self.elapsed = Duration::ZERO;
+                break;
+            }
+            on_frame(self.frame);
+        }
+    }
+
+    /// Move to the next frame. Returns `false` if there is none.
+    fn step(&mut self, clip: &SpriteClip) -> bool {
+        let last = clip.len() - 1;
+        match clip.mode {
+            PlaybackMode::Loop => {
+                self.frame = if self.frame >= last {
+                    0
+                } else {
+                    self.frame + 1
+                }
+            }
+            PlaybackMode::Once => {
+                if self.frame >= last {
+                    return false;
+                }
+                self.frame += 1;
+            }
+            PlaybackMode::PingPong => {
+                if self.reversed && self.frame == 0 {
+                    self.reversed = false;
+                } else if !self.reversed && self.frame >= last {
+                    self.reversed = true;
+                }
+                self.frame = if self.reversed {
+                    self.frame.min(last).saturating_sub(1)
+                } else {
+                    (self.frame + 1).min(last)
+                };
+            }
+        }
+        true
+    }
+}
+
+/// Advance every animation by the time that passed and raise the events of reached frames.
+fn advance_animations(
+    mut commands: Commands,
+    time: Res<Time>,
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut animation_query: Query<(Entity, &mut SpriteAnimation)>,
+) {
+    for (entity, mut animation) in &mut animation_query {
+        let Some(clip) = libraries
+            .get(&animation.library)
+            .and_then(|library| animation.sprite_clip(library))
+        else {
+            continue;
+        };
+        animation.advance(time.delta(), clip, |frame| {
+            for tag in clip.events(frame) {
+                commands.trigger_targets(AnimationEvent { tag: tag.clone() }, entity);
+            }
+        });
+    }
+}
+
+/// Update the texture atlas to show the current frame of the animation.
+fn update_animation_atlas(
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut animation_query: Query<(&SpriteAnimation, &mut TextureAtlas)>,
+) {
+    for (animation, mut atlas) in &mut animation_query {
+        let Some(clip) = libraries
+            .get(&animation.library)
+            .and_then(|library| animation.sprite_clip(library))
+        else {
+            continue;
+        };
+        let index = animation.atlas_index(clip);
+        if atlas.index != index {
+            atlas.index = index;
+        }
+    }
+}
+
+/// Clips of the player's [`AnimationLibrary`].
+/// The sheet has no hurt art, so its `hurt` tag was added to `ducky.aseprite.json` by hand
+/// instead of being exported from Aseprite. It shows the first idle frame and an empty cell
+/// in turn, three times for 80 ms each, so the player flickers for about half a second.
+/// Exporting the sheet again drops the tag unless it is recreated in Aseprite.
+const IDLE_CLIP: &str = "idle";
+const WALK_CLIP: &str = "walk";
+const HURT_CLIP: &str = "hurt";
+
+/// The player's animation states. They walk while their `speed` parameter is above 0,
+/// and walk for at least a moment once they start, so that tapping a key doesn't flicker.
+pub fn player_state_machine() -> AnimationStateMachine {
+    AnimationStateMachine::new("idle", IDLE_CLIP)
+        .with_state("walk", WALK_CLIP)
+        .with_transition(
+            AnimationTransition::new("idle", "walk")
+                .when(AnimationCondition::Above("speed".to_string(), 0.0)),
+        )
+        .with_transition(
+            AnimationTransition::new("walk", "idle")
+                .when(AnimationCondition::NotSet("speed".to_string()))
+                .with_exit(TransitionExit::AfterSecs(0.1)),
+        )
+}
+
+/// Update the state machine's parameters from the player's movement.
+fn update_player_animation(
+    mut player_query: Query<(&MovementController, &mut AnimationStateMachine), With<Player>>,
+) {
+    for (controller, mut state_machine) in &mut player_query {
+        state_machine.set_param("speed", controller.0.length());
+    }
+}
+
+/// Play the hurt clip over the player's current state when they take damage,
+/// unless [`Invulnerable`] absorbs it.
+fn play_hurt_animation(
+    trigger: Trigger<Damage>,
+    mut commands: Commands,
+    player_query: Query<(), (With<Player>, Without<Invulnerable>)>,
+) {
+    if player_query.contains(trigger.entity()) {
+        commands.trigger_targets(PlayOneShot::new(HURT_CLIP, 1), trigger.entity());
+    }
+}
+
+/// Frames tagged with this play a step sound effect.
+pub(super) const FOOTSTEP_TAG: &str = "footstep";
+
+fn play_step_sfx(trigger: Trigger<AnimationEvent>, mut commands: Commands) {
+    if trigger.event().tag == FOOTSTEP_TAG {
+        commands.trigger(PlaySfx::RandomStep);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashMap;
+
+    use super::*;
+
+    const FRAME_MS: u64 = 100;
+
+    fn clip(len: usize, mode: PlaybackMode) -> SpriteClip {
+        SpriteClip {
+            first: 10,
+            last: 10 + len - 1,
+            durations: vec![Duration::from_millis(FRAME_MS); len],
+            mode,
+            events: HashMap::default(),
+        }
+    }
+
+    /// Advance a fresh animation of `clip` by `steps` deltas of `delta_ms`,
+    /// returning every frame that was reached.
+    fn play(clip: &SpriteClip, steps: usize, delta_ms: u64) -> (SpriteAnimation, Vec<usize>) {
+        let mut animation = SpriteAnimation::new(Handle::default(), "clip");
+        let mut frames = Vec::new();
+        for _ in 0..steps {
+            animation.advance(Duration::from_millis(delta_ms), clip, |frame| {
+                frames.push(frame)
+            });
+        }
+        (animation, frames)
+    }
+
+    #[test]
+    fn loop_starts_over_after_the_last_frame() {
+        let (_, frames) = play(&clip(3, PlaybackMode::Loop), 4, FRAME_MS);
+        assert_eq!(frames, [0, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn once_stops_on_the_last_frame() {
+        let clip = clip(3, PlaybackMode::Once);
+        let (animation, frames) = play(&clip, 5, FRAME_MS);
+        assert_eq!(frames, [0, 1, 2]);
+        assert!(animation.finished());
+        assert_eq!(animation.atlas_index(&clip), clip.last);
+    }
+
+    #[test]
+    fn ping_pong_plays_back_and_forth() {
+        let (_, frames) = play(&clip(3, PlaybackMode::PingPong), 5, FRAME_MS);
+        assert_eq!(frames, [0, 1, 2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn one_frame_ping_pong_stays_on_its_frame() {
+        let (animation, frames) = play(&clip(1, PlaybackMode::PingPong), 3, FRAME_MS);
+        assert_eq!(frames, [0, 0, 0, 0]);
+        assert!(!animation.finished());
+    }
+
+    #[test]
+    fn long_step_reaches_every_skipped_frame() {
+        let clip = clip(3, PlaybackMode::Loop);
+        let (mut animation, frames) = play(&clip, 1, FRAME_MS * 2 + FRAME_MS / 2);
+        assert_eq!(frames, [0, 1, 2]);
+
+        // The rest of the long step carries over to the next frame.
+        let mut frames = Vec::new();
+        animation.advance(Duration::from_millis(FRAME_MS / 2), &clip, |frame| {
+            frames.push(frame)
+        });
+        assert_eq!(frames, [0]);
+    }
+
+    #[test]
+    fn skipped_frames_raise_their_events() {
+        let mut clip = clip(4, PlaybackMode::Once);
+        clip.events.insert(1, vec!["footstep".to_string()]);
+        clip.events
+            .insert(2, vec!["footstep".to_string(), "dust".to_string()]);
+
+        let mut animation = SpriteAnimation::new(Handle::default(), "clip");
+        let mut tags = Vec::new();
+        animation.advance(Duration::from_millis(FRAME_MS * 3), &clip, |frame| {
+            tags.extend(clip.events(frame).iter().cloned())
+        });
+        assert_eq!(tags, ["footstep", "footstep", "dust"]);
+    }
+}
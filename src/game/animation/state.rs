@@ -0,0 +1,258 @@
+//! A state machine choosing which clip a [`SpriteAnimation`] plays.
+//! Base states switch between each other through transitions with conditions on named
+//! parameters, and one-shot clips like hurt or death play over them until they finish.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{library::AnimationLibrary, SpriteAnimation};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AnimationStateMachine>();
+    app.observe(play_one_shot);
+    app.observe(log_transitions);
+}
+
+/// The animation states of an entity and how it moves between them.
+/// Systems set its parameters, e.g. how fast the entity moves, and the state machine
+/// plays the clip of the state whose conditions match.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct AnimationStateMachine {
+    /// The clip of every state, by the state's name.
+    pub states: HashMap<String, String>,
+    /// Checked in order, the first transition that can be taken is taken.
+    pub transitions: Vec<AnimationTransition>,
+    pub params: HashMap<String, f32>,
+    current: String,
+    /// Seconds since the current state was entered.
+    current_secs: f32,
+    one_shot: Option<PlayOneShot>,
+}
+
+impl AnimationStateMachine {
+    /// A state machine starting in `state`, which plays `clip`.
+    pub fn new(state: impl Into<String>, clip: impl Into<String>) -> Self {
+        let state = state.into();
+        Self {
+            states: HashMap::from([(state.clone(), clip.into())]),
+            transitions: Vec::new(),
+            params: HashMap::default(),
+            current: state,
+            current_secs: 0.0,
+            one_shot: None,
+        }
+    }
+
+    pub fn with_state(mut self, state: impl Into<String>, clip: impl Into<String>) -> Self {
+        self.states.insert(state.into(), clip.into());
+        self
+    }
+
+    pub fn with_transition(mut self, transition: AnimationTransition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    pub fn set_param(&mut self, name: &str, value: f32) {
+        if self.params.get(name) != Some(&value) {
+            self.params.insert(name.to_string(), value);
+        }
+    }
+
+    fn param(&self, name: &str) -> f32 {
+        self.params.get(name).copied().unwrap_or_default()
+    }
+
+    /// The clip of `state`, or the clip named like the state if it has none.
+    fn clip<'a>(&'a self, state: &'a str) -> &'a str {
+        self.states.get(state).map_or(state, String::as_str)
+    }
+}
+
+/// A way from one state to another.
+#[derive(Reflect, Debug, Clone)]
+pub struct AnimationTransition {
+    /// The state this transition leaves, or `None` to leave any state.
+    pub from: Option<String>,
+    pub to: String,
+    /// All of these have to hold for the transition to be taken.
+    pub conditions: Vec<AnimationCondition>,
+    pub exit: TransitionExit,
+}
+
+impl AnimationTransition {
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: Some(from.into()),
+            to: to.into(),
+            conditions: Vec::new(),
+            exit: TransitionExit::Immediate,
+        }
+    }
+
+    pub fn when(mut self, condition: AnimationCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn with_exit(self, exit: TransitionExit) -> Self {
+        Self { exit, ..self }
+    }
+}
+
+/// A condition on a parameter of an [`AnimationStateMachine`].
+/// Parameters that were never set are 0.
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub enum AnimationCondition {
+    Above(String, f32),
+    Below(String, f32),
+    /// Not 0.
+    IsSet(String),
+    NotSet(String),
+}
+
+impl AnimationCondition {
+    fn holds(&self, machine: &AnimationStateMachine) -> bool {
+        match self {
+            Self::Above(name, value) => machine.param(name) > *value,
+            Self::Below(name, value) => machine.param(name) < *value,
+            Self::IsSet(name) => machine.param(name) != 0.0,
+            Self::NotSet(name) => machine.param(name) == 0.0,
+        }
+    }
+}
+
+/// When a transition may leave its state.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Default)]
+pub enum TransitionExit {
+    /// As soon as its conditions hold.
+    #[default]
+    Immediate,
+    /// Once the state has played for this many seconds.
+    AfterSecs(f32),
+    /// Once the state's clip has finished.
+    WhenFinished,
+}
+
+/// Trigger this event on an entity with an [`AnimationStateMachine`] to play a clip once
+/// over its current state. It is ignored while a one-shot with a higher priority plays,
+/// or if the entity's library doesn't have the clip.
+#[derive(Event, Reflect, Debug, Clone)]
+pub struct PlayOneShot {
+    pub clip: String,
+    pub priority: u32,
+    /// Keep showing the last frame instead of returning to the current state, e.g. for death.
+    pub hold: bool,
+}
+
+impl PlayOneShot {
+    pub fn new(clip: impl Into<String>, priority: u32) -> Self {
+        Self {
+            clip: clip.into(),
+            priority,
+            hold: false,
+        }
+    }
+}
+
+/// Triggered on an entity when its [`AnimationStateMachine`] switches to another clip.
+/// `from` and `to` name clips, not states, since one-shots have no state.
+#[derive(Event, Debug, Clone)]
+pub struct AnimationTransitioned {
+    pub from: String,
+    pub to: String,
+}
+
+fn log_transitions(trigger: Trigger<AnimationTransitioned>) {
+    let AnimationTransitioned { from, to } = trigger.event();
+    debug!("{} animation: {from} -> {to}", trigger.entity());
+}
+
+fn play_one_shot(
+    trigger: Trigger<PlayOneShot>,
+    mut commands: Commands,
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut machine_query: Query<(&mut AnimationStateMachine, &mut SpriteAnimation)>,
+) {
+    let entity = trigger.entity();
+    let Ok((mut machine, mut animation)) = machine_query.get_mut(entity) else {
+        return;
+    };
+    let one_shot = trigger.event();
+    let has_clip = libraries
+        .get(&animation.library)
+        .is_some_and(|library| library.clip(&one_shot.clip).is_some());
+    let outranked = machine
+        .one_shot
+        .as_ref()
+        .is_some_and(|active| active.priority > one_shot.priority);
+    if !has_clip || outranked {
+        return;
+    }
+
+    let from = match &machine.one_shot {
+        Some(active) => active.clip.clone(),
+        None => machine.clip(&machine.current).to_string(),
+    };
+    animation.restart(&one_shot.clip);
+    machine.one_shot = Some(one_shot.clone());
+    commands.trigger_targets(
+        AnimationTransitioned {
+            from,
+            to: one_shot.clip.clone(),
+        },
+        entity,
+    );
+}
+
+/// Return from finished one-shots and take the first transition that can be taken.
+pub(super) fn update_state_machines(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut machine_query: Query<(Entity, &mut AnimationStateMachine, &mut SpriteAnimation)>,
+) {
+    for (entity, mut machine, mut animation) in &mut machine_query {
+        machine.current_secs += time.delta_seconds();
+
+        if let Some(one_shot) = &machine.one_shot {
+            if one_shot.hold || !animation.finished() {
+                continue;
+            }
+            let from = one_shot.clip.clone();
+            machine.one_shot = None;
+            let to = machine.clip(&machine.current).to_string();
+            animation.restart(&to);
+            commands.trigger_targets(AnimationTransitioned { from, to }, entity);
+        }
+
+        let Some(to) = machine
+            .transitions
+            .iter()
+            .find(|transition| {
+                transition
+                    .from
+                    .as_ref()
+                    .is_none_or(|from| *from == machine.current)
+                    && transition.to != machine.current
+                    && transition
+                        .conditions
+                        .iter()
+                        .all(|condition| condition.holds(&machine))
+                    && match transition.exit {
+                        TransitionExit::Immediate => true,
+                        TransitionExit::AfterSecs(secs) => machine.current_secs >= secs,
+                        TransitionExit::WhenFinished => animation.finished(),
+                    }
+            })
+            .map(|transition| transition.to.clone())
+        else {
+            continue;
+        };
+        let from = machine.clip(&machine.current).to_string();
+        machine.current = to;
+        machine.current_secs = 0.0;
+        let to = machine.clip(&machine.current).to_string();
+        animation.play(&to);
+        commands.trigger_targets(AnimationTransitioned { from, to }, entity);
+    }
+}
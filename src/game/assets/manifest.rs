@@ -0,0 +1,193 @@
+//! The asset manifest overrides where [`AssetKey`]s are loaded from, along with loader
+//! settings like the image sampler. Keys it doesn't list use the path in their
+//! `#[asset(path = ...)]` attribute. It is compiled into the game from `assets/manifest.ron`,
+//! so that it is available before anything loads, also on the web.
+
+use std::{error::Error, fmt};
+
+use bevy::{
+    asset::ron,
+    prelude::*,
+    render::texture::{ImageLoaderSettings, ImageSampler},
+    utils::HashMap,
+};
+use serde::Deserialize;
+
+use super::{AssetKey, CampaignKey, ImageKey, ParticleKey, SfxKey, SoundtrackKey, SpriteSheetKey};
+use crate::game::{
+    animation::aseprite::SpriteSheet, level::campaign::Campaign, particles::effect::ParticleEffect,
+};
+
+/// Where [`AssetKey`]s are loaded from instead of their default paths, by key type.
+#[derive(Resource, Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AssetManifest {
+    pub images: ManifestSection<ImageKey>,
+    pub sfx: ManifestSection<SfxKey>,
+    pub soundtracks: ManifestSection<SoundtrackKey>,
+    pub campaigns: ManifestSection<CampaignKey>,
+    pub sprite_sheets: ManifestSection<SpriteSheetKey>,
+    pub particles: ManifestSection<ParticleKey>,
+}
+
+pub type ManifestSection<K> = HashMap<K, ManifestEntry>;
+
+impl AssetManifest {
+    /// Parse and check the manifest compiled into the game.
+    pub fn embedded() -> Result<Self, AssetManifestError> {
+        Self::parse(include_str!("../../../assets/manifest.ron"))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, AssetManifestError> {
+        let manifest = ron::de::from_str::<Self>(text)?;
+        manifest.check::<ImageKey>()?;
+        manifest.check::<SfxKey>()?;
+        manifest.check::<SoundtrackKey>()?;
+        manifest.check::<CampaignKey>()?;
+        manifest.check::<SpriteSheetKey>()?;
+        manifest.check::<ParticleKey>()?;
+        Ok(manifest)
+    }
+
+    /// Make sure every entry for keys of type `K` has a path.
+    /// Keys without an entry have a default path, which the derive macro makes sure of.
+    fn check<K: AssetKey>(&self) -> Result<(), AssetManifestError> {
+        if let Some(key) = K::section(self)
+            .iter()
+            .find_map(|(key, entry)| entry.path.trim().is_empty().then_some(key))
+        {
+            return Err(AssetManifestError::EmptyPath {
+                section: K::SECTION,
+                key: format!("{key:?}"),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Where one asset is loaded from, and how.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Relative to the `assets` directory.
+    pub path: String,
+    /// Only used by images.
+    #[serde(default)]
+    pub sampler: Sampler,
+}
+
+/// How an image is sampled when it is drawn larger or smaller than it is.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampler {
+    /// Whatever the app's default sampler is.
+    #[default]
+    Default,
+    /// Keep pixels sharp, e.g. for pixel art.
+    Nearest,
+    /// Blend neighboring pixels.
+    Linear,
+}
+
+/// Assets that can be loaded from a [`ManifestEntry`].
+pub trait LoadAsset: Asset + Sized {
+    fn load(asset_server: &AssetServer, entry: &ManifestEntry) -> Handle<Self> {
+        asset_server.load(entry.path.clone())
+    }
+}
+
+impl LoadAsset for Image {
+    fn load(asset_server: &AssetServer, entry: &ManifestEntry) -> Handle<Self> {
+        let sampler = match entry.sampler {
+            Sampler::Default => ImageSampler::Default,
+            Sampler::Nearest => ImageSampler::nearest(),
+            Sampler::Linear => ImageSampler::linear(),
+        };
+        asset_server.load_with_settings(
+            entry.path.clone(),
+            move |settings: &mut ImageLoaderSettings| {
+                settings.sampler = sampler.clone();
+            },
+        )
+    }
+}
+
+impl LoadAsset for AudioSource {}
+
+impl LoadAsset for Campaign {}
+
+impl LoadAsset for SpriteSheet {}
+
+impl LoadAsset for ParticleEffect {}
+
+#[derive(Debug)]
+pub enum AssetManifestError {
+    Ron(ron::error::SpannedError),
+    /// A key's path is empty.
+    EmptyPath {
+        section: &'static str,
+        key: String,
+    },
+}
+
+impl fmt::Display for AssetManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ron(error) => write!(f, "could not parse the asset manifest: {error}"),
+            Self::EmptyPath { section, key } => {
+                write!(
+                    f,
+                    "the asset manifest has no path for {section} entry {key}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for AssetManifestError {}
+
+impl From<ron::error::SpannedError> for AssetManifestError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_manifest_is_valid() {
+        AssetManifest::embedded().unwrap();
+    }
+
+    #[test]
+    fn entries_override_default_paths() {
+        let manifest = AssetManifest::parse(
+            r#"(images: {Tileset: (path: "images/other.png", sampler: Linear)})"#,
+        )
+        .unwrap();
+        let entry = ImageKey::Tileset.entry(&manifest);
+        assert_eq!(entry.path, "images/other.png");
+        assert_eq!(entry.sampler, Sampler::Linear);
+        // Keys without an entry keep the path of their attribute.
+        assert_eq!(
+            SfxKey::Step1.entry(&manifest),
+            SfxKey::Step1.default_entry()
+        );
+    }
+
+    #[test]
+    fn invalid_manifests_are_rejected() {
+        assert!(matches!(
+            AssetManifest::parse(r#"(sfx: {Step1: (path: " ")})"#),
+            Err(AssetManifestError::EmptyPath { section: "sfx", .. })
+        ));
+        assert!(matches!(
+            AssetManifest::parse(r#"(sfx: {Step9: (path: "audio/sfx/step9.ogg")})"#),
+            Err(AssetManifestError::Ron(_))
+        ));
+        assert!(matches!(
+            AssetManifest::parse(r#"(sprites: {})"#),
+            Err(AssetManifestError::Ron(_))
+        ));
+    }
+}
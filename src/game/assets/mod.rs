@@ -0,0 +1,136 @@
+pub mod manifest;
+
+use std::{fmt::Debug, hash::Hash};
+
+use bevy::{prelude::*, utils::HashMap};
+use cchasers_macros::AssetKey;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use self::manifest::{AssetManifest, LoadAsset, ManifestEntry, ManifestSection};
+use super::{
+    animation::aseprite::SpriteSheet, level::campaign::Campaign, particles::effect::ParticleEffect,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    // The handle maps below are loaded from the manifest.
+    let manifest = AssetManifest::embedded().unwrap_or_else(|error| panic!("{error}"));
+    app.insert_resource(manifest);
+
+    ImageKey::register(app);
+    SfxKey::register(app);
+    SoundtrackKey::register(app);
+    CampaignKey::register(app);
+    SpriteSheetKey::register(app);
+    ParticleKey::register(app);
+}
+
+#[derive(AssetKey, Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Deserialize)]
+#[asset(asset = Image, section = "images")]
+pub enum ImageKey {
+    #[asset(path = "images/tileset.png", sampler = "nearest")]
+    Tileset,
+}
+
+#[derive(AssetKey, Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Deserialize)]
+#[asset(asset = AudioSource, section = "sfx")]
+pub enum SfxKey {
+    #[asset(path = "audio/sfx/button_hover.ogg")]
+    ButtonHover,
+    #[asset(path = "audio/sfx/button_press.ogg")]
+    ButtonPress,
+    #[asset(path = "audio/sfx/step1.ogg")]
+    Step1,
+    #[asset(path = "audio/sfx/step2.ogg")]
+    Step2,
+    #[asset(path = "audio/sfx/step3.ogg")]
+    Step3,
+    #[asset(path = "audio/sfx/step4.ogg")]
+    Step4,
+}
+
+#[derive(AssetKey, Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Serialize, Deserialize)]
+#[asset(asset = AudioSource, section = "soundtracks")]
+pub enum SoundtrackKey {
+    #[asset(path = "audio/soundtracks/Monkeys Spinning Monkeys.ogg")]
+    Credits,
+    #[asset(path = "audio/soundtracks/Fluffing A Duck.ogg")]
+    Gameplay,
+}
+
+#[derive(AssetKey, Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Deserialize)]
+#[asset(asset = Campaign, section = "campaigns")]
+pub enum CampaignKey {
+    #[asset(path = "levels/main.campaign.ron")]
+    Main,
+}
+
+#[derive(AssetKey, Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Deserialize)]
+#[asset(asset = SpriteSheet, section = "sprite_sheets")]
+pub enum SpriteSheetKey {
+    // The image's sampler is set by the Aseprite importer.
+    #[asset(path = "images/ducky.aseprite.json")]
+    Ducky,
+}
+
+#[derive(AssetKey, Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Deserialize)]
+#[asset(asset = ParticleEffect, section = "particles")]
+pub enum ParticleKey {
+    #[asset(path = "particles/dust.particles.ron")]
+    Dust,
+    #[asset(path = "particles/feathers.particles.ron")]
+    Feathers,
+    #[asset(path = "particles/sparkles.particles.ron")]
+    Sparkles,
+}
+
+/// A set of assets of one type, named by the variants of an enum.
+/// Derive it with `#[derive(AssetKey)]`, see [`cchasers_macros::AssetKey`].
+pub trait AssetKey: Copy + Eq + Hash + Debug + DeserializeOwned + Send + Sync + 'static {
+    type Asset: LoadAsset;
+    /// The name of the manifest's section for this key type.
+    const SECTION: &'static str;
+    const ALL: &'static [Self];
+
+    fn section(manifest: &AssetManifest) -> &ManifestSection<Self>;
+
+    /// Where the asset is loaded from unless the manifest says otherwise.
+    fn default_entry(self) -> ManifestEntry;
+
+    fn entry(self, manifest: &AssetManifest) -> ManifestEntry {
+        Self::section(manifest)
+            .get(&self)
+            .cloned()
+            .unwrap_or_else(|| self.default_entry())
+    }
+}
+
+#[derive(Resource, Reflect, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct HandleMap<K: AssetKey>(HashMap<K, Handle<K::Asset>>);
+
+impl<K: AssetKey, T> From<T> for HandleMap<K>
+where
+    T: Into<HashMap<K, Handle<K::Asset>>>,
+{
+    fn from(value: T) -> Self {
+        Self(value.into())
+    }
+}
+
+impl<K: AssetKey> HandleMap<K> {
+    /// Start loading every key's asset from where the [`AssetManifest`] says.
+    pub fn from_manifest(world: &World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let manifest = world.resource::<AssetManifest>();
+        K::ALL
+            .iter()
+            .map(|&key| (key, K::Asset::load(asset_server, &key.entry(manifest))))
+            .collect::<HashMap<_, _>>()
+            .into()
+    }
+
+    pub fn all_loaded(&self, asset_server: &AssetServer) -> bool {
+        self.values()
+            .all(|x| asset_server.is_loaded_with_dependencies(x))
+    }
+}
@@ -0,0 +1,184 @@
+//! Make the camera follow the player (or anything else marked with [`CameraTarget`])
+//! around levels that are larger than the window, and shake it (see [`shake`]).
+//! Local co-op players share one view until they split up, see [`split`].
+
+pub mod resolution;
+pub mod shake;
+pub mod split;
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+use self::shake::CameraShake;
+use super::{movement::Movement, tilemap::Tilemap};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((resolution::plugin, shake::plugin, split::plugin));
+
+    app.register_type::<(CameraFollow, CameraTarget)>();
+    // Follow targets after everything has moved in `Update`.
+    app.add_systems(
+        PostUpdate,
+        follow_targets
+            .before(TransformSystem::TransformPropagate)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(OnExit(Screen::Playing), reset_camera);
+}
+
+/// Settings for a camera that follows all [`CameraTarget`]s.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct CameraFollow {
+    /// Follow only this entity instead of all [`CameraTarget`]s, e.g. one player in split-screen.
+    pub target: Option<Entity>,
+    /// How quickly the camera catches up with its targets, in 1/s.
+    pub smoothing: f32,
+    /// Half the size of the rectangle around the screen center in which
+    /// targets can move without moving the camera, in pixels.
+    pub deadzone: Vec2,
+    /// How far ahead of the targets to look, in seconds of their velocity.
+    pub look_ahead_secs: f32,
+    /// Space to keep around targets when framing several of them, in pixels.
+    pub margin: f32,
+    /// How far the camera may zoom out to keep several targets on screen.
+    pub max_zoom_out: f32,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            target: None,
+            smoothing: 5.0,
+            deadzone: Vec2::new(64.0, 48.0),
+            look_ahead_secs: 0.3,
+            margin: 192.0,
+            max_zoom_out: 2.0,
+        }
+    }
+}
+
+/// Marks entities the camera keeps in view. With several targets,
+/// the camera frames all of them, e.g. both players in co-op or the player and a boss.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct CameraTarget;
+
+fn follow_targets(
+    time: Res<Time>,
+    target_query: Query<(&Transform, Option<&Movement>), With<CameraTarget>>,
+    tilemap_query: Query<(&Tilemap, &GlobalTransform)>,
+    mut camera_query: Query<
+        (&CameraFollow, &mut Transform, &mut OrthographicProjection),
+        Without<CameraTarget>,
+    >,
+) {
+    let level_bounds = tilemap_query
+        .iter()
+        .map(|(tilemap, transform)| {
+            let origin = transform.translation().xy();
+            let size = tilemap.size().as_vec2() * tilemap.tile_size();
+            Rect::from_corners(origin, origin + Vec2::new(size.x, -size.y))
+        })
+        .reduce(|a, b| a.union(b));
+    let dt = time.delta_seconds();
+
+    for (follow, mut transform, mut projection) in &mut camera_query {
+        let Some(targets) = target_bounds(&target_query, follow.target) else {
+            continue;
+        };
+        // The area the camera shows without zooming out.
+        let view = projection.area.size() / projection.scale;
+        let smoothing = 1.0 - (-follow.smoothing * dt).exp();
+
+        // Zoom out just enough to fit all targets.
+        let needed = targets.rect.size() + 2.0 * follow.margin;
+        let zoom = (needed / view)
+            .max_element()
+            .clamp(1.0, follow.max_zoom_out);
+        projection.scale = projection.scale.lerp(zoom, smoothing);
+
+        // Only move once the focus point leaves the deadzone.
+        let focus = targets.rect.center() + targets.velocity * follow.look_ahead_secs;
+        let camera_position = transform.translation.xy();
+        let offset = focus - camera_position;
+        let outside = offset - offset.clamp(-follow.deadzone, follow.deadzone);
+        let mut position = camera_position.lerp(camera_position + outside, smoothing);
+
+        // Don't show anything outside of the level.
+        if let Some(bounds) = level_bounds {
+            let half_view = view * projection.scale / 2.0;
+            let min = bounds.min + half_view;
+            let max = bounds.max - half_view;
+            position = Vec2::new(
+                clamp_or_center(position.x, min.x, max.x),
+                clamp_or_center(position.y, min.y, max.y),
+            );
+        }
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+struct TargetBounds {
+    rect: Rect,
+    /// The average velocity of all targets.
+    velocity: Vec2,
+}
+
+/// The area covered by all targets, or only by `target` if there is one.
+fn target_bounds(
+    target_query: &Query<(&Transform, Option<&Movement>), With<CameraTarget>>,
+    target: Option<Entity>,
+) -> Option<TargetBounds> {
+    let mut bounds: Option<TargetBounds> = None;
+    let mut count = 0.0;
+    let targets: Vec<_> = match target {
+        Some(target) => target_query.get(target).into_iter().collect(),
+        None => target_query.iter().collect(),
+    };
+    for (transform, movement) in targets {
+        let position = transform.translation.xy();
+        let velocity = movement.map_or(Vec2::ZERO, |movement| movement.velocity);
+        let bounds = bounds.get_or_insert(TargetBounds {
+            rect: Rect::from_corners(position, position),
+            velocity: Vec2::ZERO,
+        });
+        bounds.rect = bounds.rect.union_point(position);
+        bounds.velocity += velocity;
+        count += 1.0;
+    }
+    bounds.map(|bounds| TargetBounds {
+        velocity: bounds.velocity / count,
+        ..bounds
+    })
+}
+
+/// Clamp `value` to `min..=max`, or return the middle if the range is empty
+/// because the level is smaller than the view.
+fn clamp_or_center(value: f32, min: f32, max: f32) -> f32 {
+    if min > max {
+        (min + max) / 2.0
+    } else {
+        value.clamp(min, max)
+    }
+}
+
+fn reset_camera(
+    mut camera_query: Query<
+        (
+            &mut Transform,
+            &mut OrthographicProjection,
+            Option<&mut CameraShake>,
+        ),
+        With<CameraFollow>,
+    >,
+) {
+    for (mut transform, mut projection, shake) in &mut camera_query {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        projection.scale = 1.0;
+        if let Some(mut shake) = shake {
+            shake.clear();
+        }
+    }
+}
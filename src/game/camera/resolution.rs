@@ -0,0 +1,152 @@
+//! Render at a fixed virtual resolution that is scaled up by a whole number,
+//! so that pixel art stays crisp at every window size.
+//! Space left over by the scaling is letterboxed with the [`ClearColor`].
+
+use bevy::{
+    prelude::*,
+    render::camera::{CameraUpdateSystem, ScalingMode, Viewport},
+    transform::TransformSystem,
+    window::PrimaryWindow,
+};
+
+use super::split::SplitViewport;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<VirtualResolution>();
+    app.init_resource::<VirtualResolution>();
+    app.add_systems(
+        PostUpdate,
+        (
+            (fit_to_window, apply_letterbox)
+                .chain()
+                .before(CameraUpdateSystem),
+            snap_to_pixels.after(TransformSystem::TransformPropagate),
+        ),
+    );
+}
+
+/// The size of the virtual screen, and how it is currently scaled to the window.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct VirtualResolution {
+    /// Size of the virtual screen in virtual pixels.
+    pub size: UVec2,
+    /// How many world units one virtual pixel covers.
+    /// Our 16 pixel tiles are 64 units wide, so one of their pixels covers 4 units.
+    pub pixel_size: f32,
+    /// Whether to round rendered positions to whole virtual pixels.
+    pub snap: bool,
+    /// How many physical pixels one virtual pixel covers in the current window.
+    pub scale: u32,
+    /// The area of the window the virtual screen is drawn to, in physical pixels.
+    pub area: URect,
+}
+
+impl Default for VirtualResolution {
+    fn default() -> Self {
+        Self {
+            size: UVec2::new(320, 180),
+            pixel_size: 4.0,
+            snap: true,
+            scale: 1,
+            area: URect::default(),
+        }
+    }
+}
+
+impl VirtualResolution {
+    /// Size of the virtual screen in world units.
+    pub fn world_size(&self) -> Vec2 {
+        self.size.as_vec2() * self.pixel_size
+    }
+}
+
+/// Pick the largest whole scale that fits the window, center the scaled screen and scale the UI to match.
+fn fit_to_window(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut resolution: ResMut<VirtualResolution>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let window_size = window.physical_size();
+    // Keep the previous layout while the window is minimized.
+    if window_size.min_element() == 0 {
+        return;
+    }
+    let fit = window_size / resolution.size.max(UVec2::ONE);
+    let scale = fit.min_element().max(1);
+    let size = (resolution.size * scale).min(window_size);
+    let min = (window_size - size) / 2;
+    let area = URect::from_corners(min, min + size);
+    if resolution.scale != scale || resolution.area != area {
+        resolution.scale = scale;
+        resolution.area = area;
+    }
+
+    // Lay out the UI as if the virtual screen was one logical pixel per world unit.
+    let new_ui_scale =
+        scale as f32 / (resolution.pixel_size * window.scale_factor()).max(f32::EPSILON);
+    if ui_scale.0 != new_ui_scale {
+        ui_scale.0 = new_ui_scale;
+    }
+}
+
+/// Fit every camera's viewport into the letterboxed area, or into its part of it in split-screen.
+fn apply_letterbox(
+    resolution: Res<VirtualResolution>,
+    mut camera_query: Query<
+        (
+            &mut Camera,
+            &mut OrthographicProjection,
+            Option<&SplitViewport>,
+        ),
+        With<Camera2d>,
+    >,
+) {
+    for (mut camera, mut projection, split_viewport) in &mut camera_query {
+        let area = split_viewport.map_or(resolution.area, |split| split.rect(resolution.area));
+        // Only touch cameras that need it, to keep change detection quiet.
+        let up_to_date = camera.viewport.as_ref().is_some_and(|viewport| {
+            viewport.physical_position == area.min && viewport.physical_size == area.size()
+        });
+        if !up_to_date {
+            camera.viewport = Some(Viewport {
+                physical_position: area.min,
+                physical_size: area.size(),
+                ..default()
+            });
+        }
+        let world_size = area.size().as_vec2() / resolution.scale as f32 * resolution.pixel_size;
+        let up_to_date = matches!(
+            projection.scaling_mode,
+            ScalingMode::Fixed { width, height } if width == world_size.x && height == world_size.y
+        );
+        if !up_to_date {
+            projection.scaling_mode = ScalingMode::Fixed {
+                width: world_size.x,
+                height: world_size.y,
+            };
+        }
+    }
+}
+
+/// Round the rendered position of sprites and cameras to whole virtual pixels,
+/// so that sprites don't shimmer when they move.
+fn snap_to_pixels(
+    resolution: Res<VirtualResolution>,
+    mut transform_query: Query<&mut GlobalTransform, Or<(With<Sprite>, With<Camera2d>)>>,
+) {
+    if !resolution.snap {
+        return;
+    }
+    let pixel_size = resolution.pixel_size;
+    for mut transform in &mut transform_query {
+        let mut affine = transform.affine();
+        let snapped = (affine.translation.xy() / pixel_size).round() * pixel_size;
+        affine.translation.x = snapped.x;
+        affine.translation.y = snapped.y;
+        *transform = GlobalTransform::from(affine);
+    }
+}
@@ -0,0 +1,160 @@
+//! Trauma-based screen shake. Events add [`Trauma`], which decays over time
+//! and shakes the camera by the square of its current amount.
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+use super::follow_targets;
+use crate::game::{
+    chaser::{ChaserTrapped, PlayerCaught},
+    health::{Damage, Health, Invulnerable},
+    settings::Settings,
+    spawn::player::Player,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<CameraShake>();
+    app.add_systems(
+        PostUpdate,
+        (
+            remove_shake.before(follow_targets),
+            apply_shake.after(follow_targets),
+        )
+            .before(TransformSystem::TransformPropagate),
+    );
+    app.observe(add_trauma);
+    app.observe(shake_on_player_damage);
+    app.observe(shake_on_caught);
+    app.observe(shake_on_trapped);
+}
+
+/// Trigger this event to shake all cameras with a [`CameraShake`].
+/// The amount is added to their trauma, which is capped at 1.
+#[derive(Event, Debug)]
+pub struct Trauma(pub f32);
+
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct CameraShake {
+    /// Between 0 (still) and 1 (shaking as much as possible).
+    pub trauma: f32,
+    /// How much trauma is lost per second.
+    pub decay: f32,
+    /// Offset at full trauma, in pixels.
+    pub max_offset: f32,
+    /// Rotation at full trauma, in radians.
+    pub max_angle: f32,
+    /// How quickly the shake changes direction.
+    pub frequency: f32,
+    /// Seed for the noise, so that different cameras shake differently.
+    pub seed: u32,
+    /// The offset applied this frame, removed again before the camera moves.
+    offset: Vec2,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.5,
+            max_offset: 24.0,
+            max_angle: 0.05,
+            frequency: 15.0,
+            seed: 0,
+            offset: Vec2::ZERO,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn with_seed(self, seed: u32) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Stop shaking immediately, e.g. when the camera is reset.
+    pub fn clear(&mut self) {
+        self.trauma = 0.0;
+        self.offset = Vec2::ZERO;
+    }
+}
+
+fn add_trauma(trigger: Trigger<Trauma>, mut shake_query: Query<&mut CameraShake>) {
+    for mut shake in &mut shake_query {
+        shake.trauma = (shake.trauma + trigger.event().0).clamp(0.0, 1.0);
+    }
+}
+
+fn remove_shake(mut camera_query: Query<(&mut Transform, &mut CameraShake)>) {
+    for (mut transform, mut shake) in &mut camera_query {
+        transform.translation -= shake.offset.extend(0.0);
+        transform.rotation = Quat::IDENTITY;
+        shake.offset = Vec2::ZERO;
+    }
+}
+
+fn apply_shake(
+    // Keep shaking while the game is frozen by a hit stop.
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut camera_query: Query<(&mut Transform, &mut CameraShake)>,
+) {
+    let t = time.elapsed_seconds();
+    for (mut transform, mut shake) in &mut camera_query {
+        shake.trauma = (shake.trauma - shake.decay * time.delta_seconds()).max(0.0);
+        let amount = shake.trauma * shake.trauma * settings.screen_shake.factor();
+        if amount == 0.0 {
+            continue;
+        }
+        let x = t * shake.frequency;
+        let noise = Vec3::new(
+            noise(shake.seed, x),
+            noise(shake.seed.wrapping_add(1), x),
+            noise(shake.seed.wrapping_add(2), x),
+        );
+        shake.offset = noise.xy() * shake.max_offset * amount;
+        transform.translation += shake.offset.extend(0.0);
+        transform.rotation = Quat::from_rotation_z(noise.z * shake.max_angle * amount);
+    }
+}
+
+/// Smooth 1D value noise between -1 and 1.
+fn noise(seed: u32, x: f32) -> f32 {
+    let cell = x.floor();
+    let t = x - cell;
+    let a = hash(seed, cell as i32);
+    let b = hash(seed, cell as i32 + 1);
+    a.lerp(b, t * t * (3.0 - 2.0 * t))
+}
+
+/// A pseudo-random value between -1 and 1 for every seed and integer.
+fn hash(seed: u32, n: i32) -> f32 {
+    let mut h = seed.wrapping_mul(0x27d4_eb2d) ^ (n as u32).wrapping_mul(0x9e37_79b9);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// Ignore damage that [`Invulnerable`] is going to absorb.
+fn shake_on_player_damage(
+    trigger: Trigger<Damage>,
+    mut commands: Commands,
+    player_query: Query<&Health, (With<Player>, Without<Invulnerable>)>,
+) {
+    if player_query
+        .get(trigger.entity())
+        .is_ok_and(|health| health.current > 0)
+    {
+        commands.trigger(Trauma(0.4));
+    }
+}
+
+fn shake_on_caught(_trigger: Trigger<PlayerCaught>, mut commands: Commands) {
+    commands.trigger(Trauma(0.5));
+}
+
+fn shake_on_trapped(trigger: Trigger<ChaserTrapped>, mut commands: Commands) {
+    let trauma = if trigger.event().boss { 0.8 } else { 0.3 };
+    commands.trigger(Trauma(trauma));
+}
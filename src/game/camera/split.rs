@@ -0,0 +1,198 @@
+//! Split the screen between local co-op players once they are too far apart
+//! to share one view, and merge it back into one view when they come together again.
+
+use bevy::{prelude::*, render::camera::ClearColorConfig, transform::TransformSystem};
+
+use super::{follow_targets, resolution::VirtualResolution, shake::CameraShake, CameraFollow};
+use crate::{game::spawn::player::Player, screen::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(SplitScreen, SplitViewport)>();
+    app.init_resource::<SplitScreen>();
+    app.add_systems(
+        PostUpdate,
+        (update_split_screen, apply_split_screen)
+            .chain()
+            .before(follow_targets)
+            .before(TransformSystem::TransformPropagate)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(OnExit(Screen::Playing), merge_views);
+}
+
+/// Whether the screen is currently split between players.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct SplitScreen {
+    pub split: bool,
+}
+
+/// Which part of the virtual screen a camera draws to.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct SplitViewport {
+    pub index: usize,
+    /// How many views share the screen.
+    pub count: usize,
+}
+
+impl Default for SplitViewport {
+    fn default() -> Self {
+        Self { index: 0, count: 1 }
+    }
+}
+
+impl SplitViewport {
+    /// The part of `area` this view covers. Two views sit side by side,
+    /// three or four share the screen in a grid.
+    pub fn rect(self, area: URect) -> URect {
+        let grid = match self.count {
+            0 | 1 => return area,
+            2 => UVec2::new(2, 1),
+            _ => UVec2::new(2, 2),
+        };
+        let size = area.size() / grid;
+        let cell = UVec2::new(self.index as u32 % grid.x, self.index as u32 / grid.x);
+        let min = area.min + cell * size;
+        URect::from_corners(min, min + size)
+    }
+}
+
+/// Marks the cameras added for every player after the first while the screen is split.
+/// The first player keeps using the main camera.
+#[derive(Component, Debug)]
+struct SplitCamera;
+
+/// Split when the players need this much of the main camera's zoomed out view, and merge
+/// again below [`MERGE_THRESHOLD`], so that the views don't flicker at the border.
+const SPLIT_THRESHOLD: f32 = 1.0;
+const MERGE_THRESHOLD: f32 = 0.8;
+
+fn update_split_screen(
+    mut split_screen: ResMut<SplitScreen>,
+    resolution: Res<VirtualResolution>,
+    player_query: Query<&Transform, With<Player>>,
+    main_camera_query: Query<&CameraFollow, Without<SplitCamera>>,
+) {
+    let Ok(follow) = main_camera_query.get_single() else {
+        return;
+    };
+    let mut positions = player_query
+        .iter()
+        .map(|transform| transform.translation.xy());
+    let Some(first) = positions.next() else {
+        return;
+    };
+    let bounds = positions.fold(Rect::from_corners(first, first), |bounds, position| {
+        bounds.union_point(position)
+    });
+    let needed = bounds.size() + 2.0 * follow.margin;
+    let available = resolution.world_size() * follow.max_zoom_out;
+    let usage = (needed / available).max_element();
+
+    let threshold = if split_screen.split {
+        MERGE_THRESHOLD
+    } else {
+        SPLIT_THRESHOLD
+    };
+    let split = player_query.iter().len() > 1 && usage > threshold;
+    if split_screen.split != split {
+        split_screen.split = split;
+    }
+}
+
+fn apply_split_screen(
+    mut commands: Commands,
+    split_screen: Res<SplitScreen>,
+    player_query: Query<(Entity, &Player, &Transform)>,
+    mut main_camera_query: Query<
+        (&mut CameraFollow, &mut SplitViewport, &Transform),
+        (Without<SplitCamera>, Without<Player>),
+    >,
+    mut split_camera_query: Query<
+        (
+            &mut Camera,
+            &mut CameraFollow,
+            &mut SplitViewport,
+            &mut Transform,
+        ),
+        (With<SplitCamera>, Without<Player>),
+    >,
+) {
+    if !split_screen.is_changed() {
+        return;
+    }
+    let Ok((mut main_follow, mut main_viewport, main_transform)) =
+        main_camera_query.get_single_mut()
+    else {
+        return;
+    };
+    let mut players: Vec<_> = player_query.iter().collect();
+    players.sort_by_key(|(_, player, _)| player.index);
+
+    if !split_screen.split {
+        main_follow.target = None;
+        *main_viewport = SplitViewport::default();
+        for (mut camera, ..) in &mut split_camera_query {
+            camera.is_active = false;
+        }
+        return;
+    }
+
+    let count = players.len();
+    if let Some(&(entity, ..)) = players.first() {
+        main_follow.target = Some(entity);
+        *main_viewport = SplitViewport { index: 0, count };
+    }
+    for (index, &(entity, _, player_transform)) in players.iter().enumerate().skip(1) {
+        let viewport = SplitViewport { index, count };
+        let existing = split_camera_query
+            .iter_mut()
+            .find(|(_, _, split_viewport, _)| split_viewport.index == index);
+        if let Some((mut camera, mut follow, mut split_viewport, mut transform)) = existing {
+            camera.is_active = true;
+            follow.target = Some(entity);
+            *split_viewport = viewport;
+            // Start from the shared view instead of wherever the camera was last time.
+            transform.translation = main_transform.translation;
+            continue;
+        }
+        commands.spawn((
+            Name::new(format!("Player {} Camera", index + 1)),
+            Camera2dBundle {
+                camera: Camera {
+                    order: index as isize,
+                    // The main camera already cleared the window.
+                    clear_color: ClearColorConfig::None,
+                    ..default()
+                },
+                transform: Transform::from_translation(
+                    player_transform
+                        .translation
+                        .xy()
+                        .extend(main_transform.translation.z),
+                ),
+                ..default()
+            },
+            CameraFollow {
+                target: Some(entity),
+                ..default()
+            },
+            CameraShake::default().with_seed(index as u32),
+            viewport,
+            SplitCamera,
+            StateScoped(Screen::Playing),
+        ));
+    }
+}
+
+fn merge_views(
+    mut split_screen: ResMut<SplitScreen>,
+    mut main_camera_query: Query<(&mut CameraFollow, &mut SplitViewport), Without<SplitCamera>>,
+) {
+    split_screen.split = false;
+    for (mut follow, mut viewport) in &mut main_camera_query {
+        follow.target = None;
+        *viewport = SplitViewport::default();
+    }
+}
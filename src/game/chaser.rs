@@ -0,0 +1,110 @@
+//! Chasers hunt the player. They are released by enemy spawners,
+//! catch the player on contact and are trapped when hazardous tiles kill them.
+
+use bevy::prelude::*;
+
+use super::{
+    health::Died,
+    movement::MovementController,
+    spawn::{
+        enemy::{Boss, Chaser, EnemySpawner, SpawnChaser},
+        player::Player,
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            tick_enemy_spawners.in_set(AppSet::TickTimers),
+            chase_player.in_set(AppSet::RecordInput),
+            catch_player.in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.observe(trap_chasers);
+}
+
+/// Triggered when a chaser walks into a hazard and is removed from the level.
+#[derive(Event, Debug)]
+pub struct ChaserTrapped {
+    pub boss: bool,
+}
+
+/// Triggered on a player when a chaser touches them.
+#[derive(Event, Debug)]
+pub struct PlayerCaught;
+
+/// Spawners stop releasing chasers while this many are alive.
+const MAX_CHASERS: usize = 16;
+
+fn tick_enemy_spawners(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spawner_query: Query<(&mut EnemySpawner, &Transform)>,
+    chaser_query: Query<(), With<Chaser>>,
+) {
+    let mut alive = chaser_query.iter().count();
+    for (mut spawner, transform) in &mut spawner_query {
+        spawner.timer.tick(time.delta());
+        if spawner.timer.just_finished() && alive < MAX_CHASERS {
+            alive += 1;
+            commands.trigger(SpawnChaser {
+                translation: transform.translation.xy(),
+                boss: false,
+                components: Vec::new(),
+            });
+        }
+    }
+}
+
+/// Steer every chaser towards the closest player.
+fn chase_player(
+    player_query: Query<&Transform, With<Player>>,
+    mut chaser_query: Query<(&Transform, &mut MovementController), With<Chaser>>,
+) {
+    for (transform, mut controller) in &mut chaser_query {
+        let position = transform.translation.xy();
+        let closest = player_query
+            .iter()
+            .map(|player| player.translation.xy())
+            .min_by(|a, b| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            });
+        controller.0 = closest.map_or(Vec2::ZERO, |target| (target - position).normalize_or_zero());
+    }
+}
+
+/// Remove chasers that died, which can only happen on hazardous tiles.
+fn trap_chasers(
+    trigger: Trigger<Died>,
+    mut commands: Commands,
+    chaser_query: Query<Has<Boss>, With<Chaser>>,
+) {
+    let entity = trigger.entity();
+    let Ok(boss) = chaser_query.get(entity) else {
+        return;
+    };
+    commands.entity(entity).despawn_recursive();
+    commands.trigger(ChaserTrapped { boss });
+}
+
+/// How close a chaser has to get to catch the player.
+const CATCH_DISTANCE: f32 = 64.0;
+
+fn catch_player(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    chaser_query: Query<&Transform, With<Chaser>>,
+) {
+    for (entity, player) in &player_query {
+        let caught = chaser_query.iter().any(|chaser| {
+            player.translation.xy().distance(chaser.translation.xy()) < CATCH_DISTANCE
+        });
+        if caught {
+            commands.trigger_targets(PlayerCaught, entity);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Checkpoints let the player continue from where they touched one
+//! instead of failing the level when they are caught or killed.
+
+use bevy::prelude::*;
+
+use super::{
+    chaser::PlayerCaught,
+    health::{Died, Health},
+    juice::Landed,
+    movement::Movement,
+    spawn::{checkpoint::Checkpoint, player::Player},
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LastCheckpoint>();
+    app.add_systems(
+        Update,
+        reach_checkpoints
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.observe(respawn_at_checkpoint);
+    app.observe(revive_at_checkpoint);
+}
+
+/// The position of the checkpoint the player touched most recently.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct LastCheckpoint(pub Vec2);
+
+/// How close the player has to get to a checkpoint to activate it.
+const CHECKPOINT_REACH_DISTANCE: f32 = 48.0;
+
+fn reach_checkpoints(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform, Option<&LastCheckpoint>), With<Player>>,
+    checkpoint_query: Query<&Transform, With<Checkpoint>>,
+) {
+    for (entity, player, last_checkpoint) in &player_query {
+        let reached = checkpoint_query.iter().find(|checkpoint| {
+            player
+                .translation
+                .xy()
+                .distance(checkpoint.translation.xy())
+                < CHECKPOINT_REACH_DISTANCE
+        });
+        let Some(checkpoint) = reached else {
+            continue;
+        };
+        let position = checkpoint.translation.xy();
+        if last_checkpoint.is_none_or(|last| last.0 != position) {
+            commands.entity(entity).insert(LastCheckpoint(position));
+        }
+    }
+}
+
+/// Bring the caught player back to their last checkpoint.
+fn respawn_at_checkpoint(
+    trigger: Trigger<PlayerCaught>,
+    mut commands: Commands,
+    mut player_query: Query<(&mut Transform, &LastCheckpoint), With<Player>>,
+) {
+    let Ok((mut transform, checkpoint)) = player_query.get_mut(trigger.entity()) else {
+        return;
+    };
+    transform.translation = checkpoint.0.extend(transform.translation.z);
+    commands.trigger_targets(Landed, trigger.entity());
+}
+
+/// Bring the player back to their last checkpoint with full health.
+fn revive_at_checkpoint(
+    trigger: Trigger<Died>,
+    mut commands: Commands,
+    mut player_query: Query<
+        (&mut Transform, &mut Movement, &mut Health, &LastCheckpoint),
+        With<Player>,
+    >,
+) {
+    let Ok((mut transform, mut movement, mut health, checkpoint)) =
+        player_query.get_mut(trigger.entity())
+    else {
+        return;
+    };
+    transform.translation = checkpoint.0.extend(transform.translation.z);
+    movement.velocity = Vec2::ZERO;
+    health.current = health.max;
+    commands.trigger_targets(Landed, trigger.entity());
+}
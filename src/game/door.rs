@@ -0,0 +1,123 @@
+//! Doors block the way until a signal opens them, or the player unlocks them with a key.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    level::{components::ComponentDef, PickupKind},
+    pickup::PickupCollected,
+    spawn::{door::Door, player::Player},
+    tilemap::{TileId, Tilemap},
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<KeyRing>();
+    app.add_systems(
+        Update,
+        (unlock_doors, update_door_tiles)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.observe(set_door_open);
+    app.observe(collect_keys);
+}
+
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct DoorDef {
+    /// Wires open and close all doors with this name.
+    #[serde(default)]
+    pub name: String,
+    pub position: UVec2,
+    /// Locked doors ignore signals and open for good when the player brings a key.
+    #[serde(default)]
+    pub locked: bool,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[reflect(ignore)]
+    pub components: Vec<ComponentDef>,
+}
+
+/// The keys a player carries.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct KeyRing {
+    pub keys: u32,
+}
+
+/// Trigger this event to open or close all doors with the given name.
+#[derive(Event, Debug)]
+pub struct SetDoorOpen {
+    pub name: String,
+    pub open: bool,
+}
+
+fn set_door_open(trigger: Trigger<SetDoorOpen>, mut door_query: Query<&mut Door>) {
+    let event = trigger.event();
+    for mut door in &mut door_query {
+        if door.name == event.name && !door.locked && door.open != event.open {
+            door.open = event.open;
+        }
+    }
+}
+
+/// Give the key to the player who collected it.
+fn collect_keys(
+    trigger: Trigger<PickupCollected>,
+    mut commands: Commands,
+    mut player_query: Query<Option<&mut KeyRing>, With<Player>>,
+) {
+    if trigger.event().0 != PickupKind::Key {
+        return;
+    }
+    let player = trigger.entity();
+    match player_query.get_mut(player) {
+        Ok(Some(mut key_ring)) => key_ring.keys += 1,
+        Ok(None) => {
+            commands.entity(player).insert(KeyRing { keys: 1 });
+        }
+        Err(_) => {}
+    }
+}
+
+/// How close the player has to get to a locked door to unlock it.
+/// The door blocks the player, so this has to cover the player's collider.
+const UNLOCK_DISTANCE: f32 = 96.0;
+
+fn unlock_doors(
+    mut player_query: Query<(&Transform, &mut KeyRing), With<Player>>,
+    mut door_query: Query<(&Transform, &mut Door)>,
+) {
+    for (player, mut key_ring) in &mut player_query {
+        for (transform, mut door) in &mut door_query {
+            if key_ring.keys == 0 || !door.locked {
+                continue;
+            }
+            if player.translation.xy().distance(transform.translation.xy()) < UNLOCK_DISTANCE {
+                key_ring.keys -= 1;
+                door.locked = false;
+                door.open = true;
+            }
+        }
+    }
+}
+
+/// Closed doors are solid tiles, open doors show the level's tile underneath.
+fn update_door_tiles(
+    door_query: Query<&Door, Changed<Door>>,
+    mut tilemap_query: Query<&mut Tilemap>,
+) {
+    for door in &door_query {
+        let tile = if door.open {
+            door.open_tile
+        } else {
+            TileId::DOOR
+        };
+        for mut tilemap in &mut tilemap_query {
+            if tilemap.get(door.tile) != tile {
+                tilemap.set(door.tile, tile);
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Tiles that affect everything moving across them, chasers included:
+//! ice and mud change how entities move, conveyors push them,
+//! spikes hurt them and pits kill them (or send the player back).
+
+use bevy::prelude::*;
+
+use super::{
+    checkpoint::LastCheckpoint,
+    health::{Damage, Died},
+    juice::Landed,
+    movement::{apply_movement, Movement, MovementModifiers},
+    spawn::player::{Player, SpawnPoint},
+    tilemap::{
+        tileset::{Hazard, Surface},
+        TileId, Tilemap,
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (apply_surfaces.before(apply_movement), hurt_on_hazards)
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Traction on ice, as a fraction of the normal traction.
+const ICE_TRACTION: f32 = 0.1;
+/// Speed in mud, as a fraction of the normal speed.
+const MUD_SPEED_MULTIPLIER: f32 = 0.5;
+/// How fast conveyors push whatever stands on them, in pixels per second.
+const CONVEYOR_SPEED: f32 = 150.0;
+/// Damage dealt by spikes. Damage is dealt again once the
+/// [`Invulnerable`](super::health::Invulnerable) period ends.
+const SPIKE_DAMAGE: u32 = 1;
+/// Damage dealt to the player when falling into a pit, on top of being sent back.
+const PIT_DAMAGE: u32 = 1;
+
+/// The tile under `position` in world space.
+fn tile_at(tilemap_query: &Query<(&Tilemap, &GlobalTransform)>, position: Vec2) -> TileId {
+    tilemap_query
+        .iter()
+        .find_map(|(tilemap, transform)| {
+            tilemap
+                .world_to_tile(transform, position)
+                .map(|tile| tilemap.get(tile))
+        })
+        .unwrap_or(TileId::EMPTY)
+}
+
+fn apply_surfaces(
+    mut commands: Commands,
+    tilemap_query: Query<(&Tilemap, &GlobalTransform)>,
+    mut movement_query: Query<(Entity, &Transform, Option<&mut MovementModifiers>), With<Movement>>,
+) {
+    for (entity, transform, modifiers) in &mut movement_query {
+        let tile = tile_at(&tilemap_query, transform.translation.xy());
+        let mut new_modifiers = MovementModifiers::default();
+        match tile.properties().surface {
+            Surface::Normal => {}
+            Surface::Ice => new_modifiers.traction = ICE_TRACTION,
+            Surface::Mud => new_modifiers.speed_multiplier = MUD_SPEED_MULTIPLIER,
+            Surface::Conveyor { direction } => {
+                // Tile coordinates grow downwards, world coordinates upwards.
+                let direction = Vec2::new(direction.x as f32, -direction.y as f32);
+                new_modifiers.push = direction * CONVEYOR_SPEED;
+            }
+        }
+        if let Some(mut modifiers) = modifiers {
+            modifiers.set_if_neq(new_modifiers);
+        } else {
+            commands.entity(entity).insert(new_modifiers);
+        }
+    }
+}
+
+fn hurt_on_hazards(
+    mut commands: Commands,
+    tilemap_query: Query<(&Tilemap, &GlobalTransform)>,
+    mut movement_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut Movement,
+        Option<&SpawnPoint>,
+        Option<&LastCheckpoint>,
+        Has<Player>,
+    )>,
+) {
+    for (entity, mut transform, mut movement, spawn_point, checkpoint, is_player) in
+        &mut movement_query
+    {
+        let tile = tile_at(&tilemap_query, transform.translation.xy());
+        match tile.properties().hazard {
+            None => {}
+            Some(Hazard::Spikes) => {
+                commands.trigger_targets(
+                    Damage {
+                        amount: SPIKE_DAMAGE,
+                    },
+                    entity,
+                );
+            }
+            Some(Hazard::Pit) if is_player => {
+                let respawn = checkpoint
+                    .map(|checkpoint| checkpoint.0)
+                    .or(spawn_point.map(|spawn_point| spawn_point.0));
+                if let Some(respawn) = respawn {
+                    transform.translation = respawn.extend(transform.translation.z);
+                    movement.velocity = Vec2::ZERO;
+                    commands.trigger_targets(Landed, entity);
+                }
+                commands.trigger_targets(Damage { amount: PIT_DAMAGE }, entity);
+            }
+            Some(Hazard::Pit) => commands.trigger_targets(Died, entity),
+        }
+    }
+}
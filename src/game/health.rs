@@ -0,0 +1,80 @@
+//! Health, damage and death. Damage is dealt by triggering [`Damage`] on an entity,
+//! which triggers [`Died`] on it once its health runs out.
+
+use bevy::prelude::*;
+
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(Health, Invulnerable)>();
+    app.add_systems(Update, tick_invulnerability.in_set(AppSet::TickTimers));
+    app.observe(apply_damage);
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Ignores all damage until the timer finishes.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Invulnerable(pub Timer);
+
+/// How long an entity can't be damaged again after taking damage.
+const INVULNERABILITY_SECS: f32 = 0.5;
+
+/// Trigger this event on an entity to reduce its [`Health`].
+#[derive(Event, Debug)]
+pub struct Damage {
+    pub amount: u32,
+}
+
+/// Triggered on an entity when its [`Health`] runs out or it is killed outright.
+/// Observers decide what happens next, e.g. despawning it or respawning the player.
+#[derive(Event, Debug)]
+pub struct Died;
+
+fn apply_damage(
+    trigger: Trigger<Damage>,
+    mut commands: Commands,
+    mut health_query: Query<&mut Health, Without<Invulnerable>>,
+) {
+    let entity = trigger.entity();
+    let Ok(mut health) = health_query.get_mut(entity) else {
+        return;
+    };
+    if health.current == 0 {
+        return;
+    }
+    health.current = health.current.saturating_sub(trigger.event().amount);
+    commands
+        .entity(entity)
+        .insert(Invulnerable(Timer::from_seconds(
+            INVULNERABILITY_SECS,
+            TimerMode::Once,
+        )));
+    if health.current == 0 {
+        commands.trigger_targets(Died, entity);
+    }
+}
+
+fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut invulnerable_query: Query<(Entity, &mut Invulnerable)>,
+) {
+    for (entity, mut invulnerable) in &mut invulnerable_query {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Briefly slow down the game when big hits land, so that they feel heavier.
+
+use bevy::prelude::*;
+
+use super::{
+    chaser::{ChaserTrapped, PlayerCaught},
+    health::Died,
+    settings::Settings,
+    spawn::player::Player,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<HitStopTimer>();
+    app.init_resource::<HitStopTimer>();
+    app.add_systems(PreUpdate, tick_hit_stop);
+    app.observe(start_hit_stop);
+    app.observe(hit_stop_on_caught);
+    app.observe(hit_stop_on_boss_trapped);
+    app.observe(hit_stop_on_player_died);
+}
+
+/// Trigger this event to slow down [`Time<Virtual>`] to `time_scale` for `secs` real seconds.
+/// A longer or stronger hit stop replaces one that is already running.
+#[derive(Event, Debug)]
+pub struct HitStop {
+    pub secs: f32,
+    pub time_scale: f32,
+}
+
+/// The running hit stop, measured in real time since virtual time is slowed down.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct HitStopTimer {
+    remaining_secs: f32,
+    time_scale: f32,
+}
+
+fn start_hit_stop(
+    trigger: Trigger<HitStop>,
+    settings: Res<Settings>,
+    mut timer: ResMut<HitStopTimer>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let event = trigger.event();
+    let secs = event.secs * settings.hit_stop.factor();
+    if secs <= 0.0 {
+        return;
+    }
+    if timer.remaining_secs > 0.0 {
+        timer.remaining_secs = timer.remaining_secs.max(secs);
+        timer.time_scale = timer.time_scale.min(event.time_scale);
+    } else {
+        timer.remaining_secs = secs;
+        timer.time_scale = event.time_scale;
+    }
+    time.set_relative_speed(timer.time_scale);
+}
+
+fn tick_hit_stop(
+    real_time: Res<Time<Real>>,
+    mut timer: ResMut<HitStopTimer>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if timer.remaining_secs <= 0.0 {
+        return;
+    }
+    timer.remaining_secs -= real_time.delta_seconds();
+    if timer.remaining_secs <= 0.0 {
+        time.set_relative_speed(1.0);
+    }
+}
+
+fn hit_stop_on_caught(_trigger: Trigger<PlayerCaught>, mut commands: Commands) {
+    commands.trigger(HitStop {
+        secs: 0.15,
+        time_scale: 0.05,
+    });
+}
+
+fn hit_stop_on_boss_trapped(trigger: Trigger<ChaserTrapped>, mut commands: Commands) {
+    if trigger.event().boss {
+        commands.trigger(HitStop {
+            secs: 0.3,
+            time_scale: 0.1,
+        });
+    }
+}
+
+fn hit_stop_on_player_died(
+    trigger: Trigger<Died>,
+    mut commands: Commands,
+    player_query: Query<(), With<Player>>,
+) {
+    if player_query.contains(trigger.entity()) {
+        commands.trigger(HitStop {
+            secs: 0.2,
+            time_scale: 0.05,
+        });
+    }
+}
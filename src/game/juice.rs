@@ -0,0 +1,275 @@
+//! Procedural animation that gives characters weight: squash and stretch when they
+//! speed up or slow down, a bob while walking, and springy scale punches.
+//! Every effect contributes to the entity's [`Juice`], which combines them on top of the
+//! scale the entity was spawned with, so they never overwrite it or each other.
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use super::{
+    animation::{AnimationEvent, FOOTSTEP_TAG},
+    health::{Damage, Invulnerable},
+    movement::{apply_movement, Movement},
+    spawn::player::Player,
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(Juice, SquashStretch, WalkBob, ScaleSpring)>();
+    app.observe(capture_base_scale);
+    app.observe(punch_scale);
+    app.observe(bob_on_footsteps);
+    app.add_systems(
+        Update,
+        (
+            squash_and_stretch,
+            update_walk_bobs,
+            update_scale_springs,
+            apply_juice,
+        )
+            .chain()
+            .after(apply_movement)
+            .in_set(AppSet::Update),
+    );
+
+    // Punch the player when things happen to them.
+    app.observe(squash_on_landing);
+    app.observe(flinch_on_hit);
+}
+
+/// Combines the juice effects of an entity and applies them to its [`Transform`] and [`Sprite`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Juice {
+    /// The scale without any juice, taken from the [`Transform`] when this is added.
+    /// Change this instead of the scale to resize the entity.
+    pub base_scale: Vec3,
+    /// Multiplies the width and height, from [`SquashStretch`].
+    stretch: Vec2,
+    /// Added to the width and height multipliers, from [`ScaleSpring`].
+    punch: Vec2,
+    /// How far the sprite is raised, as a fraction of its height, from [`WalkBob`].
+    bob: f32,
+}
+
+impl Default for Juice {
+    fn default() -> Self {
+        Self {
+            base_scale: Vec3::ONE,
+            stretch: Vec2::ONE,
+            punch: Vec2::ZERO,
+            bob: 0.0,
+        }
+    }
+}
+
+fn capture_base_scale(
+    trigger: Trigger<OnAdd, Juice>,
+    mut juice_query: Query<(&Transform, &mut Juice)>,
+) {
+    if let Ok((transform, mut juice)) = juice_query.get_mut(trigger.entity()) {
+        juice.base_scale = transform.scale;
+    }
+}
+
+fn apply_juice(mut juice_query: Query<(&Juice, &mut Transform, Option<&mut Sprite>)>) {
+    for (juice, mut transform, sprite) in &mut juice_query {
+        let multiplier = (juice.stretch * (Vec2::ONE + juice.punch)).max(Vec2::ZERO);
+        transform.scale = juice.base_scale * multiplier.extend(1.0);
+        let Some(mut sprite) = sprite else {
+            continue;
+        };
+        // The anchor moves the sprite without moving the entity, e.g. its collider.
+        let anchor = if juice.bob == 0.0 {
+            Anchor::Center
+        } else {
+            Anchor::Custom(Vec2::new(0.0, -juice.bob))
+        };
+        if sprite.anchor != anchor {
+            sprite.anchor = anchor;
+        }
+    }
+}
+
+/// Stretches the entity along its movement when it speeds up and squashes it when it
+/// slows down, keeping its area about the same. Needs [`Movement`] and [`Juice`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct SquashStretch {
+    /// How much to stretch per pixel per second squared of acceleration.
+    pub strength: f32,
+    /// The most the entity is stretched, as a fraction of its size.
+    pub max: f32,
+    /// How quickly the stretch follows the acceleration, in 1/s.
+    pub responsiveness: f32,
+    previous_velocity: Vec2,
+    /// The current stretch along each axis.
+    amount: Vec2,
+}
+
+impl Default for SquashStretch {
+    fn default() -> Self {
+        Self {
+            strength: 0.0001,
+            max: 0.2,
+            responsiveness: 15.0,
+            previous_velocity: Vec2::ZERO,
+            amount: Vec2::ZERO,
+        }
+    }
+}
+
+fn squash_and_stretch(
+    time: Res<Time>,
+    mut squash_query: Query<(&Movement, &mut SquashStretch, &mut Juice)>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+    for (movement, mut squash, mut juice) in &mut squash_query {
+        let acceleration = (movement.velocity - squash.previous_velocity) / dt;
+        squash.previous_velocity = movement.velocity;
+        let stretch = (acceleration.length() * squash.strength).min(squash.max);
+        let direction = acceleration.normalize_or_zero().abs();
+        let slowing_down = acceleration.dot(movement.velocity) < 0.0;
+        let target = direction * if slowing_down { -stretch } else { stretch };
+        let smoothing = 1.0 - (-squash.responsiveness * dt).exp();
+        squash.amount = squash.amount.lerp(target, smoothing);
+
+        // Stretching along one axis squashes the other one.
+        let along = Vec2::ONE + squash.amount;
+        juice.stretch = along / along.yx();
+    }
+}
+
+/// Bobs the sprite up and down while walking, landing on every frame tagged as a footstep.
+/// Needs a [`SpriteAnimation`](super::animation::SpriteAnimation) and [`Juice`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct WalkBob {
+    /// How high the sprite is raised between footsteps, as a fraction of its height.
+    pub height: f32,
+    /// Seconds since the last footstep.
+    since_step: f32,
+    /// Seconds between the last two footsteps.
+    step_secs: f32,
+}
+
+impl Default for WalkBob {
+    fn default() -> Self {
+        Self {
+            height: 0.04,
+            since_step: f32::INFINITY,
+            step_secs: 0.0,
+        }
+    }
+}
+
+impl WalkBob {
+    /// Steps further apart than this are not part of the same walk.
+    const MAX_STEP_SECS: f32 = 0.5;
+}
+
+fn bob_on_footsteps(trigger: Trigger<AnimationEvent>, mut bob_query: Query<&mut WalkBob>) {
+    if trigger.event().tag != FOOTSTEP_TAG {
+        return;
+    }
+    let Ok(mut bob) = bob_query.get_mut(trigger.entity()) else {
+        return;
+    };
+    bob.step_secs = bob.since_step.min(WalkBob::MAX_STEP_SECS);
+    bob.since_step = 0.0;
+}
+
+fn update_walk_bobs(time: Res<Time>, mut bob_query: Query<(&mut WalkBob, &mut Juice)>) {
+    for (mut bob, mut juice) in &mut bob_query {
+        bob.since_step += time.delta_seconds();
+        // Rise after every footstep and come down on the next one. Once the steps stop,
+        // finish the current arc and stay down.
+        juice.bob = if bob.step_secs > 0.0 && bob.since_step < bob.step_secs {
+            let t = bob.since_step / bob.step_secs;
+            bob.height * (t * std::f32::consts::PI).sin()
+        } else {
+            0.0
+        };
+    }
+}
+
+/// A spring that punches the scale and wobbles back, see [`PunchScale`].
+/// Needs [`Juice`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct ScaleSpring {
+    /// How strongly the scale is pulled back, in 1/s².
+    pub stiffness: f32,
+    /// How quickly the wobbling dies down, in 1/s.
+    pub damping: f32,
+    offset: Vec2,
+    velocity: Vec2,
+}
+
+impl Default for ScaleSpring {
+    fn default() -> Self {
+        Self {
+            stiffness: 300.0,
+            damping: 12.0,
+            offset: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+        }
+    }
+}
+
+/// Trigger this event on an entity with a [`ScaleSpring`] to punch its width and height
+/// by a fraction of its size, e.g. `(0.2, -0.2)` to squash it flat.
+#[derive(Event, Debug)]
+pub struct PunchScale(pub Vec2);
+
+impl PunchScale {
+    pub fn uniform(amount: f32) -> Self {
+        Self(Vec2::splat(amount))
+    }
+}
+
+fn punch_scale(trigger: Trigger<PunchScale>, mut spring_query: Query<&mut ScaleSpring>) {
+    if let Ok(mut spring) = spring_query.get_mut(trigger.entity()) {
+        spring.offset += trigger.event().0;
+    }
+}
+
+/// Springs are unstable with big steps, so long frames are simulated as shorter ones.
+const MAX_SPRING_STEP_SECS: f32 = 1.0 / 30.0;
+
+fn update_scale_springs(time: Res<Time>, mut spring_query: Query<(&mut ScaleSpring, &mut Juice)>) {
+    let dt = time.delta_seconds().min(MAX_SPRING_STEP_SECS);
+    for (mut spring, mut juice) in &mut spring_query {
+        let acceleration = -spring.stiffness * spring.offset - spring.damping * spring.velocity;
+        spring.velocity += acceleration * dt;
+        let velocity = spring.velocity;
+        spring.offset += velocity * dt;
+        juice.punch = spring.offset;
+    }
+}
+
+/// Triggered on an entity when it is put down somewhere, e.g. at a checkpoint.
+#[derive(Event, Debug)]
+pub struct Landed;
+
+/// Squash the player flat when they land.
+const LANDING_SQUASH: Vec2 = Vec2::new(0.3, -0.3);
+
+/// Shrink the player a little when they are hit.
+const HIT_FLINCH: f32 = -0.2;
+
+fn squash_on_landing(trigger: Trigger<Landed>, mut commands: Commands) {
+    commands.trigger_targets(PunchScale(LANDING_SQUASH), trigger.entity());
+}
+
+fn flinch_on_hit(
+    trigger: Trigger<Damage>,
+    mut commands: Commands,
+    player_query: Query<(), (With<Player>, Without<Invulnerable>)>,
+) {
+    if player_query.contains(trigger.entity()) {
+        commands.trigger_targets(PunchScale::uniform(HIT_FLINCH), trigger.entity());
+    }
+}
@@ -0,0 +1,95 @@
+//! Decide which sprites are drawn in front of which. Every sprite is on a [`DrawLayer`],
+//! and sprites on the same layer with a [`YSort`] are drawn in front of the ones above
+//! them, so that actors walking past each other overlap the way they would from above.
+//! The z of these entities is set here, so spawn them at any z.
+
+use bevy::{prelude::*, transform::TransformSystem};
+use serde::Deserialize;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(DrawLayer, YSort)>();
+    app.add_systems(
+        PostUpdate,
+        apply_draw_layers.before(TransformSystem::TransformPropagate),
+    );
+}
+
+/// The layers sprites are drawn on, from back to front.
+/// Not to be confused with Bevy's `RenderLayers`, which pick the cameras that see an entity.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[reflect(Component, Default)]
+pub enum DrawLayer {
+    /// The level's tiles.
+    Ground,
+    /// Things lying flat on the ground, e.g. pressure plates and dust.
+    Decals,
+    /// Things standing on the ground, e.g. the player, chasers and pickups.
+    #[default]
+    Actors,
+    /// Things flying around, e.g. sparkles and feathers.
+    Effects,
+    /// Things in front of everything, e.g. tree tops and roofs.
+    Overhead,
+}
+
+/// How far apart the layers are in z. Y-sorted sprites stay within half of this
+/// around their layer's z.
+const LAYER_SPACING: f32 = 100.0;
+
+/// How much z changes per pixel of y. Levels can be this many pixels high,
+/// in either direction, before sprites start to sort wrongly:
+/// `LAYER_SPACING / 2 / Y_SORT_SCALE` = 50,000.
+const Y_SORT_SCALE: f32 = 0.001;
+
+impl DrawLayer {
+    /// The z of sprites on this layer without a [`YSort`].
+    /// The camera sees everything between -1000 and 1000.
+    pub const fn z(self) -> f32 {
+        match self {
+            Self::Ground => -2.0 * LAYER_SPACING,
+            Self::Decals => -LAYER_SPACING,
+            Self::Actors => 0.0,
+            Self::Effects => LAYER_SPACING,
+            Self::Overhead => 2.0 * LAYER_SPACING,
+        }
+    }
+}
+
+/// Draw the entity in front of everything on its [`DrawLayer`] that is further up,
+/// or on [`DrawLayer::Actors`] without one.
+/// Only works for entities without a parent, since it sorts by their own y.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Default)]
+#[reflect(Component, Default)]
+pub struct YSort {
+    /// Added to the entity's y before sorting, e.g. to sort by where its feet are
+    /// rather than by its center.
+    pub offset: f32,
+}
+
+impl YSort {
+    pub fn new(offset: f32) -> Self {
+        Self { offset }
+    }
+}
+
+fn apply_draw_layers(
+    mut layer_query: Query<
+        (Option<&DrawLayer>, Option<&YSort>, &mut Transform),
+        (
+            Or<(With<DrawLayer>, With<YSort>)>,
+            Or<(Changed<Transform>, Changed<DrawLayer>, Changed<YSort>)>,
+        ),
+    >,
+) {
+    for (layer, y_sort, mut transform) in &mut layer_query {
+        let mut z = layer.copied().unwrap_or_default().z();
+        if let Some(y_sort) = y_sort {
+            let y = transform.translation.y + y_sort.offset;
+            let half_spacing = LAYER_SPACING / 2.0;
+            z += (-y * Y_SORT_SCALE).clamp(-half_spacing, half_spacing);
+        }
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! The campaign: an ordered list of levels that unlock one after another.
+//! It is described by a `.campaign.ron` manifest in `assets/levels`.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{LevelDef, LevelId, Levels};
+use crate::game::progress::Progress;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Campaign>();
+    app.init_asset_loader::<super::loader::CampaignLoader>();
+    app.register_type::<(MedalThresholds, Medal)>();
+    app.add_systems(Update, register_campaign_levels);
+}
+
+#[derive(Asset, TypePath, Debug)]
+pub struct Campaign {
+    /// Levels in the order they are unlocked.
+    pub levels: Vec<CampaignLevel>,
+}
+
+#[derive(Debug)]
+pub struct CampaignLevel {
+    pub id: LevelId,
+    pub level: Handle<LevelDef>,
+    pub medals: MedalThresholds,
+}
+
+impl Campaign {
+    /// Whether the level at `index` can be played.
+    /// The first level is always unlocked, every other level is unlocked by completing the one before it.
+    pub fn is_unlocked(&self, index: usize, progress: &Progress) -> bool {
+        index == 0
+            || self
+                .levels
+                .get(index - 1)
+                .is_some_and(|previous| progress.is_completed(&previous.id))
+    }
+}
+
+/// The minimum score needed for each medal.
+#[derive(Copy, Clone, Debug, Reflect, Serialize, Deserialize)]
+pub struct MedalThresholds {
+    pub bronze: u32,
+    pub silver: u32,
+    pub gold: u32,
+}
+
+impl MedalThresholds {
+    /// Return the best medal earned by `score`.
+    pub fn medal(&self, score: u32) -> Option<Medal> {
+        if score >= self.gold {
+            Some(Medal::Gold)
+        } else if score >= self.silver {
+            Some(Medal::Silver)
+        } else if score >= self.bronze {
+            Some(Medal::Bronze)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Reflect)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl Medal {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bronze => "Bronze",
+            Self::Silver => "Silver",
+            Self::Gold => "Gold",
+        }
+    }
+}
+
+/// Make the levels of every loaded campaign available by their ID.
+fn register_campaign_levels(
+    mut events: EventReader<AssetEvent<Campaign>>,
+    campaigns: Res<Assets<Campaign>>,
+    mut levels: ResMut<Levels>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(campaign) = campaigns.get(*id) else {
+            continue;
+        };
+        for level in &campaign.levels {
+            levels.insert(level.id.clone(), level.level.clone());
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Extra components for level objects, described by data and inserted through reflection.
+//! This is how custom properties in [Tiled](https://www.mapeditor.org/) maps end up on entities.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    ecs::{reflect::ReflectComponent, system::EntityCommand},
+    prelude::*,
+    reflect::{GetPath, TypeRegistry},
+};
+use serde::{Deserialize, Serialize};
+
+/// Extra components for the objects a level has at most one of.
+/// Other objects list their extra components themselves.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UniqueComponents {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub player_spawn: Vec<ComponentDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exit: Vec<ComponentDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub boss: Vec<ComponentDef>,
+}
+
+impl UniqueComponents {
+    pub fn is_empty(&self) -> bool {
+        self.player_spawn.is_empty() && self.exit.is_empty() && self.boss.is_empty()
+    }
+}
+
+/// A component to insert into a spawned entity.
+/// If the entity already has the component, only the given fields are changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentDef {
+    /// The component's short type path like `Movement`, or its full type path.
+    pub component: String,
+    /// Field values by [reflection path](bevy::reflect::GetPath), e.g. `speed`.
+    #[serde(default)]
+    pub fields: BTreeMap<String, PropertyValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl PropertyValue {
+    /// Overwrite `field` with this value, converting between number types as needed.
+    /// Integers that don't fit into the field's type are an error.
+    fn apply_to(&self, field: &mut dyn Reflect) -> Result<(), String> {
+        macro_rules! set_int {
+            ($value:expr, $($ty:ty),*) => {
+                $(if let Some(field) = field.downcast_mut::<$ty>() {
+                    *field = <$ty>::try_from($value).map_err(|_| {
+                        format!("{} is out of range for a field of type {}", $value, stringify!($ty))
+                    })?;
+                    return Ok(());
+                })*
+            };
+        }
+        macro_rules! set_float {
+            ($value:expr, $($ty:ty),*) => {
+                $(if let Some(field) = field.downcast_mut::<$ty>() {
+                    *field = $value as $ty;
+                    return Ok(());
+                })*
+            };
+        }
+
+        match self {
+            Self::Bool(value) => {
+                if let Some(field) = field.downcast_mut::<bool>() {
+                    *field = *value;
+                    return Ok(());
+                }
+            }
+            Self::Int(value) => {
+                set_int!(*value, i8, i16, i32, i64, u8, u16, u32, u64, usize);
+                set_float!(*value, f32, f64);
+            }
+            Self::Float(value) => {
+                set_float!(*value, f32, f64);
+            }
+            Self::String(value) => {
+                if let Some(field) = field.downcast_mut::<String>() {
+                    value.clone_into(field);
+                    return Ok(());
+                }
+            }
+        }
+        Err(format!(
+            "cannot assign {self:?} to a field of type {}",
+            field.reflect_type_path()
+        ))
+    }
+}
+
+/// Insert or update the components described by [`ComponentDef`]s.
+/// Use it with [`EntityCommands::add`](bevy::ecs::system::EntityCommands::add)
+/// right after spawning an entity.
+pub struct ApplyComponentDefs(pub Vec<ComponentDef>);
+
+impl EntityCommand for ApplyComponentDefs {
+    fn apply(self, id: Entity, world: &mut World) {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        for component in &self.0 {
+            if let Err(error) = apply_component_def(world, id, component, &registry) {
+                warn!("Could not add component {}: {error}", component.component);
+            }
+        }
+    }
+}
+
+fn apply_component_def(
+    world: &mut World,
+    entity: Entity,
+    component: &ComponentDef,
+    registry: &TypeRegistry,
+) -> Result<(), String> {
+    let registration = registry
+        .get_with_short_type_path(&component.component)
+        .or_else(|| registry.get_with_type_path(&component.component))
+        .ok_or("the type is not registered")?;
+    let reflect_component = registration
+        .data::<ReflectComponent>()
+        .ok_or("the type is not a reflected component")?;
+    let mut entity = world.get_entity_mut(entity).ok_or("the entity is gone")?;
+
+    if let Some(mut existing) = reflect_component.reflect_mut(&mut entity) {
+        return set_fields(existing.as_reflect_mut(), &component.fields);
+    }
+    let mut value = registration
+        .data::<ReflectDefault>()
+        .ok_or("the entity does not have it and it does not reflect `Default`")?
+        .default();
+    set_fields(value.as_mut(), &component.fields)?;
+    reflect_component.insert(&mut entity, value.as_ref(), registry);
+    Ok(())
+}
+
+fn set_fields(
+    value: &mut dyn Reflect,
+    fields: &BTreeMap<String, PropertyValue>,
+) -> Result<(), String> {
+    for (path, property) in fields {
+        let field = value
+            .reflect_path_mut(path.as_str())
+            .map_err(|error| format!("invalid field {path:?}: {error}"))?;
+        property.apply_to(field)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_convert_to_the_field_type() {
+        let mut small = 0u8;
+        PropertyValue::Int(200).apply_to(&mut small).unwrap();
+        assert_eq!(small, 200);
+
+        let mut float = 0.0f32;
+        PropertyValue::Int(3).apply_to(&mut float).unwrap();
+        assert_eq!(float, 3.0);
+
+        let mut name = String::new();
+        PropertyValue::String("duck".to_string())
+            .apply_to(&mut name)
+            .unwrap();
+        assert_eq!(name, "duck");
+    }
+
+    #[test]
+    fn out_of_range_ints_are_rejected() {
+        let mut small = 7u8;
+        assert!(PropertyValue::Int(300).apply_to(&mut small).is_err());
+        let mut unsigned = 7u32;
+        assert!(PropertyValue::Int(-1).apply_to(&mut unsigned).is_err());
+        // The field keeps its value.
+        assert_eq!((small, unsigned), (7, 7));
+    }
+
+    #[test]
+    fn mismatched_types_are_rejected() {
+        let mut float = 0.0f32;
+        assert!(PropertyValue::String("fast".to_string())
+            .apply_to(&mut float)
+            .is_err());
+        let mut int = 0i32;
+        assert!(PropertyValue::Float(1.5).apply_to(&mut int).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Load [`LevelDef`]s and [`Campaign`]s from RON files.
+//! Levels can also be loaded from Tiled maps, see [`super::tiled`].
+
+use std::{error::Error, fmt, io};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext},
+    utils::HashSet,
+};
+use serde::Deserialize;
+
+use super::{
+    campaign::{Campaign, CampaignLevel, MedalThresholds},
+    tiled::TiledMapError,
+    LevelDef, LevelDefError, LevelId,
+};
+
+#[derive(Default)]
+pub struct LevelDefLoader;
+
+impl AssetLoader for LevelDefLoader {
+    type Asset = LevelDef;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let level = ron::de::from_bytes::<LevelDef>(&bytes)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// The on-disk representation of a [`Campaign`].
+#[derive(Deserialize)]
+struct CampaignFile {
+    levels: Vec<CampaignLevelFile>,
+}
+
+#[derive(Deserialize)]
+struct CampaignLevelFile {
+    id: LevelId,
+    /// Path of the level file, relative to the `assets` directory.
+    path: String,
+    medals: MedalThresholds,
+}
+
+#[derive(Default)]
+pub struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file = ron::de::from_bytes::<CampaignFile>(&bytes)?;
+
+        let mut ids = HashSet::new();
+        let mut levels = Vec::with_capacity(file.levels.len());
+        for level in file.levels {
+            if !ids.insert(level.id.clone()) {
+                return Err(LevelLoaderError::DuplicateLevel(level.id));
+            }
+            levels.push(CampaignLevel {
+                id: level.id,
+                level: load_context.load(level.path),
+                medals: level.medals,
+            });
+        }
+        Ok(Campaign { levels })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelLoaderError {
+    Io(io::Error),
+    Ron(ron::error::SpannedError),
+    Json(serde_json::Error),
+    InvalidTiledMap(TiledMapError),
+    InvalidLevel(LevelDefError),
+    /// A campaign lists the same level ID twice.
+    DuplicateLevel(LevelId),
+}
+
+impl fmt::Display for LevelLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read file: {error}"),
+            Self::Ron(error) => write!(f, "could not parse file: {error}"),
+            Self::Json(error) => write!(f, "could not parse file: {error}"),
+            Self::InvalidTiledMap(error) => write!(f, "invalid Tiled map: {error}"),
+            Self::InvalidLevel(error) => write!(f, "invalid level: {error}"),
+            Self::DuplicateLevel(id) => write!(f, "level {:?} is listed more than once", id.0),
+        }
+    }
+}
+
+impl Error for LevelLoaderError {}
+
+impl From<io::Error> for LevelLoaderError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for LevelLoaderError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+impl From<serde_json::Error> for LevelLoaderError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<TiledMapError> for LevelLoaderError {
+    fn from(error: TiledMapError) -> Self {
+        Self::InvalidTiledMap(error)
+    }
+}
+
+impl From<LevelDefError> for LevelLoaderError {
+    fn from(error: LevelDefError) -> Self {
+        Self::InvalidLevel(error)
+    }
+}
@@ -0,0 +1,504 @@
+//! Data-driven level definitions.
+//! Levels are described by `.level.ron` files in `assets/levels`
+//! and loaded as [`LevelDef`] assets by a custom [`AssetLoader`](bevy::asset::AssetLoader).
+//! Levels made with Tiled are loaded from `.tmj` files instead.
+
+pub mod campaign;
+pub mod components;
+mod loader;
+pub mod procgen;
+mod tiled;
+
+use std::{error::Error, fmt};
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use self::components::{ComponentDef, UniqueComponents};
+use super::{
+    assets::SoundtrackKey,
+    door::DoorDef,
+    objectives::ObjectiveDef,
+    tilemap::{TileId, Tilemap},
+    triggers::{TriggerDef, WireDef, WireTarget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<LevelDef>();
+    app.init_asset_loader::<loader::LevelDefLoader>();
+    app.init_asset_loader::<tiled::TiledMapLoader>();
+
+    app.register_type::<(Levels, CurrentLevel)>();
+    app.init_resource::<Levels>();
+
+    app.add_plugins(campaign::plugin);
+}
+
+/// Identifies a level across sessions, e.g. for saving progress.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Reflect, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LevelId(pub String);
+
+impl LevelId {
+    /// The ID under which the level editor registers the level it play tests.
+    pub const PLAYTEST: &'static str = "editor_playtest";
+
+    pub fn is_playtest(&self) -> bool {
+        self.0 == Self::PLAYTEST
+    }
+}
+
+/// All levels that can be spawned, by ID.
+#[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct Levels(HashMap<LevelId, Handle<LevelDef>>);
+
+/// The level that is played when entering [`Screen::Playing`](crate::screen::Screen::Playing).
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct CurrentLevel(pub LevelId);
+
+/// Trigger this event when the player beats the current level.
+#[derive(Event, Debug)]
+pub struct LevelCompleted {
+    pub score: u32,
+}
+
+/// Trigger this event when the player loses the current level.
+#[derive(Event, Debug)]
+pub struct LevelFailed {
+    pub reason: FailReason,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Reflect)]
+pub enum FailReason {
+    /// A chaser caught the player.
+    Caught,
+    /// The player's health ran out.
+    Killed,
+    /// The level's time limit ran out.
+    TimeUp,
+}
+
+/// Everything needed to build a level.
+/// Positions are given in tile coordinates, with `(0, 0)` being the top left tile.
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct LevelDef {
+    /// Display name of the level.
+    pub name: String,
+    /// Width and height of a single tile in pixels.
+    pub tile_size: f32,
+    /// The tile grid, one string per row from top to bottom.
+    /// Each character is the symbol of a tile type, see [`TileId::from_symbol`].
+    pub tiles: Vec<String>,
+    /// The tile the player spawns on.
+    pub player_spawn: UVec2,
+    #[serde(default)]
+    pub enemy_spawners: Vec<EnemySpawnerDef>,
+    #[serde(default)]
+    pub pickups: Vec<PickupDef>,
+    /// The tile the player has to reach for `ObjectiveDef::ReachExit`.
+    #[serde(default)]
+    pub exit: Option<UVec2>,
+    /// Tiles the player respawns at after being caught, once they have touched them.
+    #[serde(default)]
+    pub checkpoints: Vec<CheckpointDef>,
+    /// The tile the boss spawns on, needed for `ObjectiveDef::DefeatBoss`.
+    #[serde(default)]
+    pub boss: Option<UVec2>,
+    /// Areas that react to actors entering and leaving them.
+    #[serde(default)]
+    pub triggers: Vec<TriggerDef>,
+    /// Doors block the way until a signal opens them or the player unlocks them.
+    #[serde(default)]
+    pub doors: Vec<DoorDef>,
+    /// Connections from triggers to what they control.
+    #[serde(default)]
+    pub wires: Vec<WireDef>,
+    /// Extra components for the player, the exit and the boss.
+    #[serde(default, skip_serializing_if = "UniqueComponents::is_empty")]
+    pub components: UniqueComponents,
+    /// Everything the player has to do to complete the level.
+    pub objectives: Vec<ObjectiveDef>,
+    /// The level is failed if it is not completed within this many seconds.
+    #[serde(default)]
+    pub time_limit_secs: Option<f32>,
+    /// Completing the level faster than this earns bonus points.
+    #[serde(default = "default_par_time_secs")]
+    pub par_time_secs: f32,
+    /// The soundtrack that plays while the level is active.
+    pub soundtrack: SoundtrackKey,
+}
+
+fn default_par_time_secs() -> f32 {
+    60.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnemySpawnerDef {
+    pub position: UVec2,
+    /// Seconds between two spawned enemies.
+    pub interval_secs: f32,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupDef {
+    pub kind: PickupKind,
+    pub position: UVec2,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointDef {
+    pub position: UVec2,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Reflect, Serialize, Deserialize)]
+pub enum PickupKind {
+    Egg,
+    /// Unlocks a locked door.
+    Key,
+}
+
+impl LevelDef {
+    /// Size of the arena in tiles.
+    pub fn size(&self) -> UVec2 {
+        let width = self.tiles.first().map_or(0, |row| row.chars().count());
+        UVec2::new(width as u32, self.tiles.len() as u32)
+    }
+
+    /// Return the tile at `position`, or [`TileId::EMPTY`] if it is out of bounds.
+    pub fn tile(&self, position: UVec2) -> TileId {
+        self.tiles
+            .get(position.y as usize)
+            .and_then(|row| row.chars().nth(position.x as usize))
+            .and_then(TileId::from_symbol)
+            .unwrap_or(TileId::EMPTY)
+    }
+
+    /// Replace the tile at `position`. Does nothing if `position` is out of bounds.
+    pub fn set_tile(&mut self, position: UVec2, tile: TileId) {
+        let Some(row) = self.tiles.get_mut(position.y as usize) else {
+            return;
+        };
+        let symbol = tile.properties().symbol;
+        *row = row
+            .chars()
+            .enumerate()
+            .map(|(x, c)| if x == position.x as usize { symbol } else { c })
+            .collect();
+    }
+
+    /// Iterate over all tiles together with their position.
+    pub fn iter_tiles(&self) -> impl Iterator<Item = (UVec2, TileId)> + '_ {
+        self.tiles.iter().enumerate().flat_map(|(y, row)| {
+            row.chars().enumerate().map(move |(x, c)| {
+                let tile = TileId::from_symbol(c).unwrap_or(TileId::EMPTY);
+                (UVec2::new(x as u32, y as u32), tile)
+            })
+        })
+    }
+
+    /// Convert a tile position to the world position of the tile's center.
+    /// The arena is centered on the world origin.
+    pub fn tile_to_world(&self, position: UVec2) -> Vec2 {
+        let half_size = (self.size().as_vec2() - 1.0) / 2.0;
+        let offset = position.as_vec2() - half_size;
+        Vec2::new(offset.x, -offset.y) * self.tile_size
+    }
+
+    /// Render the tile grid as text, marking the player spawn with `P`,
+    /// enemy spawners with `E`, pickups with `*`, checkpoints with `C`, triggers with `T`,
+    /// doors with `D`, the exit with `X` and the boss with `B`.
+    pub fn to_ascii(&self) -> String {
+        let mut rows: Vec<Vec<char>> = self.tiles.iter().map(|row| row.chars().collect()).collect();
+        let mut mark = |position: UVec2, c: char| {
+            if let Some(tile) = rows
+                .get_mut(position.y as usize)
+                .and_then(|row| row.get_mut(position.x as usize))
+            {
+                *tile = c;
+            }
+        };
+        for pickup in &self.pickups {
+            mark(pickup.position, '*');
+        }
+        for spawner in &self.enemy_spawners {
+            mark(spawner.position, 'E');
+        }
+        for checkpoint in &self.checkpoints {
+            mark(checkpoint.position, 'C');
+        }
+        for trigger in &self.triggers {
+            mark(trigger.position, 'T');
+        }
+        for door in &self.doors {
+            mark(door.position, 'D');
+        }
+        if let Some(exit) = self.exit {
+            mark(exit, 'X');
+        }
+        if let Some(boss) = self.boss {
+            mark(boss, 'B');
+        }
+        mark(self.player_spawn, 'P');
+
+        rows.into_iter()
+            .map(|row| row.into_iter().collect::<String>() + "\n")
+            .collect()
+    }
+
+    /// Build a tilemap holding the level's tiles, with all doors closed.
+    pub fn to_tilemap(&self) -> Tilemap {
+        let mut tilemap = Tilemap::new(self.size(), self.tile_size);
+        for (position, tile) in self.iter_tiles() {
+            tilemap.set(position, tile);
+        }
+        for door in &self.doors {
+            tilemap.set(door.position, TileId::DOOR);
+        }
+        tilemap
+    }
+
+    /// World position of the tilemap's origin, such that the arena is centered on the world origin.
+    pub fn tilemap_origin(&self) -> Vec2 {
+        let half_extent = self.size().as_vec2() * self.tile_size / 2.0;
+        Vec2::new(-half_extent.x, half_extent.y)
+    }
+
+    /// Check that the level is well-formed.
+    pub fn validate(&self) -> Result<(), LevelDefError> {
+        let size = self.size();
+        if size.x == 0 || size.y == 0 {
+            return Err(LevelDefError::Empty);
+        }
+        for (y, row) in self.tiles.iter().enumerate() {
+            if row.chars().count() != size.x as usize {
+                return Err(LevelDefError::RaggedRow { row: y });
+            }
+            if let Some((x, c)) = row
+                .chars()
+                .enumerate()
+                .find(|(_, c)| TileId::from_symbol(*c).is_none())
+            {
+                return Err(LevelDefError::UnknownTile {
+                    position: UVec2::new(x as u32, y as u32),
+                    tile: c,
+                });
+            }
+        }
+
+        let positions = std::iter::once(self.player_spawn)
+            .chain(self.enemy_spawners.iter().map(|spawner| spawner.position))
+            .chain(self.pickups.iter().map(|pickup| pickup.position))
+            .chain(
+                self.checkpoints
+                    .iter()
+                    .map(|checkpoint| checkpoint.position),
+            )
+            .chain(self.exit)
+            .chain(self.boss)
+            .chain(self.doors.iter().map(|door| door.position))
+            .chain(
+                self.triggers
+                    .iter()
+                    .map(|trigger| trigger.position + trigger.size.saturating_sub(UVec2::ONE)),
+            );
+        for position in positions {
+            if position.cmpge(size).any() {
+                return Err(LevelDefError::OutOfBounds { position });
+            }
+        }
+
+        for wire in &self.wires {
+            if !self
+                .triggers
+                .iter()
+                .any(|trigger| trigger.name == wire.from)
+            {
+                return Err(LevelDefError::UnknownTrigger(wire.from.clone()));
+            }
+            if let WireTarget::Door(name) = &wire.to {
+                if !self.doors.iter().any(|door| door.name == *name) {
+                    return Err(LevelDefError::UnknownDoor(name.clone()));
+                }
+            }
+        }
+
+        if self.objectives.is_empty() {
+            return Err(LevelDefError::NoObjectives);
+        }
+        for objective in &self.objectives {
+            let has_target = match objective {
+                ObjectiveDef::ReachExit => self.exit.is_some(),
+                ObjectiveDef::DefeatBoss => self.boss.is_some(),
+                ObjectiveDef::CollectAll(kind) => {
+                    self.pickups.iter().any(|pickup| pickup.kind == *kind)
+                }
+                ObjectiveDef::TrapChasers { .. } => {
+                    !self.enemy_spawners.is_empty() || self.boss.is_some()
+                }
+                ObjectiveDef::Survive { .. } => true,
+            };
+            if !has_target {
+                return Err(LevelDefError::UnreachableObjective(objective.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error describing why a [`LevelDef`] is malformed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelDefError {
+    /// The tile grid has no tiles.
+    Empty,
+    /// A row of the tile grid does not have the same length as the first row.
+    RaggedRow { row: usize },
+    /// The tile grid contains an unknown character.
+    UnknownTile { position: UVec2, tile: char },
+    /// A spawn position lies outside of the tile grid.
+    OutOfBounds { position: UVec2 },
+    /// A wire starts at a trigger that does not exist.
+    UnknownTrigger(String),
+    /// A wire leads to a door that does not exist.
+    UnknownDoor(String),
+    /// The level has no objectives, so it could never be completed.
+    NoObjectives,
+    /// An objective refers to something the level does not contain, e.g. an exit.
+    UnreachableObjective(ObjectiveDef),
+}
+
+impl fmt::Display for LevelDefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "level has no tiles"),
+            Self::RaggedRow { row } => {
+                write!(f, "row {row} has a different length than the first row")
+            }
+            Self::UnknownTile { position, tile } => {
+                write!(f, "unknown tile {tile:?} at {position}")
+            }
+            Self::OutOfBounds { position } => {
+                write!(f, "position {position} is outside of the level")
+            }
+            Self::UnknownTrigger(name) => write!(f, "there is no trigger named {name:?}"),
+            Self::UnknownDoor(name) => write!(f, "there is no door named {name:?}"),
+            Self::NoObjectives => write!(f, "level has no objectives"),
+            Self::UnreachableObjective(objective) => {
+                write!(
+                    f,
+                    "objective {objective:?} cannot be completed in this level"
+                )
+            }
+        }
+    }
+}
+
+impl Error for LevelDefError {}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::ron;
+
+    use super::*;
+
+    fn parse(text: &str) -> LevelDef {
+        ron::de::from_str(text).unwrap()
+    }
+
+    /// A small valid level to break in different ways.
+    fn level() -> LevelDef {
+        parse(
+            r#"(
+                name: "Test",
+                tile_size: 64.0,
+                tiles: ["....", "....", "...."],
+                player_spawn: (1, 1),
+                exit: Some((3, 2)),
+                objectives: [ReachExit],
+                soundtrack: Gameplay,
+            )"#,
+        )
+    }
+
+    #[test]
+    fn shipped_levels_are_valid() {
+        for text in [
+            include_str!("../../../assets/levels/arena.level.ron"),
+            include_str!("../../../assets/levels/caves.level.ron"),
+            include_str!("../../../assets/levels/rooms.level.ron"),
+        ] {
+            assert_eq!(parse(text).validate(), Ok(()));
+        }
+        assert_eq!(level().validate(), Ok(()));
+    }
+
+    #[test]
+    fn malformed_tiles_are_rejected() {
+        let mut empty = level();
+        empty.tiles.clear();
+        assert_eq!(empty.validate(), Err(LevelDefError::Empty));
+
+        let mut ragged = level();
+        ragged.tiles[2].push('.');
+        assert_eq!(ragged.validate(), Err(LevelDefError::RaggedRow { row: 2 }));
+
+        let mut unknown = level();
+        unknown.tiles[1] = ".?..".to_string();
+        assert_eq!(
+            unknown.validate(),
+            Err(LevelDefError::UnknownTile {
+                position: UVec2::new(1, 1),
+                tile: '?',
+            })
+        );
+    }
+
+    #[test]
+    fn objects_outside_the_level_are_rejected() {
+        let mut level = level();
+        level.exit = Some(UVec2::new(4, 2));
+        assert_eq!(
+            level.validate(),
+            Err(LevelDefError::OutOfBounds {
+                position: UVec2::new(4, 2),
+            })
+        );
+    }
+
+    #[test]
+    fn objectives_need_something_to_complete() {
+        let mut none = level();
+        none.objectives.clear();
+        assert_eq!(none.validate(), Err(LevelDefError::NoObjectives));
+
+        let mut no_boss = level();
+        no_boss.objectives.push(ObjectiveDef::DefeatBoss);
+        assert_eq!(
+            no_boss.validate(),
+            Err(LevelDefError::UnreachableObjective(
+                ObjectiveDef::DefeatBoss
+            ))
+        );
+    }
+
+    #[test]
+    fn wires_need_existing_ends() {
+        let mut level = level();
+        level.wires = ron::de::from_str(r#"[(from: "plate", to: Door("gate"))]"#).unwrap();
+        assert_eq!(
+            level.validate(),
+            Err(LevelDefError::UnknownTrigger("plate".to_string()))
+        );
+    }
+}
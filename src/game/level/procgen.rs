@@ -0,0 +1,539 @@
+//! Seeded procedural generation of arenas.
+//! The same seed and settings always produce the same `LevelDef`.
+//! Run `cargo run --example generate_level -- <seed>` to preview generated levels as ASCII.
+
+use std::{collections::VecDeque, error::Error, fmt};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::{EnemySpawnerDef, LevelDef, PickupDef, PickupKind};
+use crate::game::objectives::ObjectiveDef;
+use crate::game::{assets::SoundtrackKey, tilemap::TileId};
+
+/// Parameters for [`generate`].
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    /// Size of the arena in tiles, including the outer wall.
+    pub size: UVec2,
+    pub tile_size: f32,
+    pub layout: Layout,
+    pub enemy_spawners: u32,
+    pub pickups: u32,
+    /// Minimum distance in tiles between the player spawn and any enemy spawner.
+    pub min_enemy_distance: f32,
+    /// Minimum distance in tiles between the player spawn and any pickup.
+    pub min_pickup_distance: f32,
+    /// Give up after this many layouts failed validation.
+    pub max_attempts: u32,
+    pub objectives: Vec<ObjectiveDef>,
+    pub soundtrack: SoundtrackKey,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            size: UVec2::new(40, 24),
+            tile_size: 64.0,
+            layout: Layout::Caves {
+                fill_ratio: 0.45,
+                smoothing_steps: 5,
+            },
+            enemy_spawners: 3,
+            pickups: 5,
+            min_enemy_distance: 10.0,
+            min_pickup_distance: 4.0,
+            max_attempts: 32,
+            objectives: vec![ObjectiveDef::Survive { secs: 60.0 }],
+            soundtrack: SoundtrackKey::Gameplay,
+        }
+    }
+}
+
+/// The algorithm used to carve out the arena.
+#[derive(Debug, Clone)]
+pub enum Layout {
+    /// Cellular-automata caves.
+    Caves {
+        /// Chance of each tile to start out as a wall.
+        fill_ratio: f32,
+        /// Number of smoothing passes over the initial noise.
+        smoothing_steps: u32,
+    },
+    /// Rectangular rooms joined by corridors.
+    Rooms {
+        max_rooms: u32,
+        min_room_size: UVec2,
+        max_room_size: UVec2,
+    },
+}
+
+impl GeneratorSettings {
+    /// Check that these settings can produce a level at all.
+    pub fn validate(&self) -> Result<(), GenerateError> {
+        if self.size.cmplt(UVec2::splat(3)).any() {
+            return Err(GenerateError::InvalidSettings(
+                "the arena must be at least 3 by 3 tiles, including the outer wall",
+            ));
+        }
+        if let Layout::Rooms {
+            min_room_size,
+            max_room_size,
+            ..
+        } = self.layout
+        {
+            if min_room_size.cmpgt(max_room_size).any() {
+                return Err(GenerateError::InvalidSettings(
+                    "the minimum room size is larger than the maximum room size",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Generate a level from `seed`.
+pub fn generate(seed: u64, settings: &GeneratorSettings) -> Result<LevelDef, GenerateError> {
+    settings.validate()?;
+    // `StdRng` is deterministic for a given version of `rand`.
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..settings.max_attempts {
+        let mut grid = match settings.layout {
+            Layout::Caves {
+                fill_ratio,
+                smoothing_steps,
+            } => caves(&mut rng, settings.size, fill_ratio, smoothing_steps),
+            Layout::Rooms {
+                max_rooms,
+                min_room_size,
+                max_room_size,
+            } => rooms(
+                &mut rng,
+                settings.size,
+                max_rooms,
+                min_room_size,
+                max_room_size,
+            ),
+        };
+        keep_largest_region(&mut grid);
+        if let Some(level) = place_spawns(&mut rng, &grid, seed, settings) {
+            if is_connected(&level) {
+                return Ok(level);
+            }
+        }
+    }
+    Err(GenerateError::NoValidLevel {
+        seed,
+        attempts: settings.max_attempts,
+    })
+}
+
+/// Whether the player can walk from their spawn to every enemy spawner and pickup.
+pub fn is_connected(level: &LevelDef) -> bool {
+    let reachable = flood_fill(level.size(), level.player_spawn, |position| {
+        !level.tile(position).properties().solid
+    });
+    level
+        .enemy_spawners
+        .iter()
+        .map(|spawner| spawner.position)
+        .chain(level.pickups.iter().map(|pickup| pickup.position))
+        .all(|position| reachable.contains(position))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerateError {
+    /// The [`GeneratorSettings`] can't produce a level.
+    InvalidSettings(&'static str),
+    /// No layout passed validation.
+    NoValidLevel { seed: u64, attempts: u32 },
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSettings(reason) => write!(f, "invalid generator settings: {reason}"),
+            Self::NoValidLevel { seed, attempts } => {
+                write!(
+                    f,
+                    "no valid level for seed {seed} after {attempts} attempts"
+                )
+            }
+        }
+    }
+}
+
+impl Error for GenerateError {}
+
+/// A grid of walls (`true`) and floors (`false`).
+#[derive(Clone)]
+struct Grid {
+    size: UVec2,
+    walls: Vec<bool>,
+}
+
+impl Grid {
+    fn filled(size: UVec2) -> Self {
+        Self {
+            size,
+            walls: vec![true; (size.x * size.y) as usize],
+        }
+    }
+
+    fn index(&self, position: UVec2) -> usize {
+        (position.y * self.size.x + position.x) as usize
+    }
+
+    fn is_wall(&self, position: UVec2) -> bool {
+        self.walls[self.index(position)]
+    }
+
+    fn set_wall(&mut self, position: UVec2, wall: bool) {
+        let index = self.index(position);
+        self.walls[index] = wall;
+    }
+
+    fn is_border(&self, position: UVec2) -> bool {
+        position.x == 0
+            || position.y == 0
+            || position.x == self.size.x - 1
+            || position.y == self.size.y - 1
+    }
+
+    fn positions(&self) -> impl Iterator<Item = UVec2> {
+        let size = self.size;
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| UVec2::new(x, y)))
+    }
+
+    fn floors(&self) -> Vec<UVec2> {
+        self.positions().filter(|&p| !self.is_wall(p)).collect()
+    }
+
+    /// Number of walls among the 8 neighbors. Out-of-bounds neighbors count as walls.
+    fn wall_neighbors(&self, position: UVec2) -> u32 {
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let neighbor = position.as_ivec2() + IVec2::new(dx, dy);
+                let in_bounds =
+                    neighbor.cmpge(IVec2::ZERO).all() && neighbor.cmplt(self.size.as_ivec2()).all();
+                if !in_bounds || self.is_wall(neighbor.as_uvec2()) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+/// The set of tiles reached by [`flood_fill`].
+struct Reachable {
+    size: UVec2,
+    tiles: Vec<bool>,
+}
+
+impl Reachable {
+    fn contains(&self, position: UVec2) -> bool {
+        position.cmplt(self.size).all()
+            && self.tiles[(position.y * self.size.x + position.x) as usize]
+    }
+
+    fn count(&self) -> usize {
+        self.tiles.iter().filter(|&&reached| reached).count()
+    }
+}
+
+/// Collect all tiles reachable from `start` through 4-connected tiles that are `passable`.
+fn flood_fill(size: UVec2, start: UVec2, passable: impl Fn(UVec2) -> bool) -> Reachable {
+    let mut reachable = Reachable {
+        size,
+        tiles: vec![false; (size.x * size.y) as usize],
+    };
+    if !start.cmplt(size).all() || !passable(start) {
+        return reachable;
+    }
+
+    let mut queue = VecDeque::from([start]);
+    reachable.tiles[(start.y * size.x + start.x) as usize] = true;
+    while let Some(position) = queue.pop_front() {
+        for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let neighbor = position.as_ivec2() + offset;
+            if neighbor.cmplt(IVec2::ZERO).any() || neighbor.cmpge(size.as_ivec2()).any() {
+                continue;
+            }
+            let neighbor = neighbor.as_uvec2();
+            let index = (neighbor.y * size.x + neighbor.x) as usize;
+            if !reachable.tiles[index] && passable(neighbor) {
+                reachable.tiles[index] = true;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    reachable
+}
+
+fn caves(rng: &mut StdRng, size: UVec2, fill_ratio: f32, smoothing_steps: u32) -> Grid {
+    let mut grid = Grid::filled(size);
+    for position in grid.positions().collect::<Vec<_>>() {
+        let wall = grid.is_border(position) || rng.gen::<f32>() < fill_ratio;
+        grid.set_wall(position, wall);
+    }
+
+    for _ in 0..smoothing_steps {
+        let previous = grid.clone();
+        for position in previous.positions() {
+            let wall = match previous.wall_neighbors(position) {
+                _ if previous.is_border(position) => true,
+                0..=3 => false,
+                4 => previous.is_wall(position),
+                _ => true,
+            };
+            grid.set_wall(position, wall);
+        }
+    }
+    grid
+}
+
+fn rooms(
+    rng: &mut StdRng,
+    size: UVec2,
+    max_rooms: u32,
+    min_room_size: UVec2,
+    max_room_size: UVec2,
+) -> Grid {
+    let mut grid = Grid::filled(size);
+    // Keep rooms inside the outer wall.
+    let max_room_size = max_room_size.min(size.saturating_sub(UVec2::splat(2)));
+    let min_room_size = min_room_size.max(UVec2::ONE).min(max_room_size);
+    if max_room_size.cmpeq(UVec2::ZERO).any() {
+        return grid;
+    }
+
+    let mut rooms: Vec<URect> = Vec::new();
+    for _ in 0..max_rooms * 4 {
+        if rooms.len() as u32 >= max_rooms {
+            break;
+        }
+        let room_size = UVec2::new(
+            rng.gen_range(min_room_size.x..=max_room_size.x),
+            rng.gen_range(min_room_size.y..=max_room_size.y),
+        );
+        let min = UVec2::new(
+            rng.gen_range(1..=size.x - 1 - room_size.x),
+            rng.gen_range(1..=size.y - 1 - room_size.y),
+        );
+        let room = URect::from_corners(min, min + room_size);
+        // Leave at least one tile of wall between rooms.
+        let padded = room.inflate(1);
+        if rooms
+            .iter()
+            .any(|other| !padded.intersect(*other).is_empty())
+        {
+            continue;
+        }
+        rooms.push(room);
+    }
+
+    for room in &rooms {
+        for y in room.min.y..room.max.y {
+            for x in room.min.x..room.max.x {
+                grid.set_wall(UVec2::new(x, y), false);
+            }
+        }
+    }
+
+    for pair in rooms.windows(2) {
+        let from = pair[0].center();
+        let to = pair[1].center();
+        let corner = if rng.gen() {
+            UVec2::new(to.x, from.y)
+        } else {
+            UVec2::new(from.x, to.y)
+        };
+        carve_line(&mut grid, from, corner);
+        carve_line(&mut grid, corner, to);
+    }
+    grid
+}
+
+/// Carve a straight horizontal or vertical corridor.
+fn carve_line(grid: &mut Grid, from: UVec2, to: UVec2) {
+    let min = from.min(to);
+    let max = from.max(to);
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            grid.set_wall(UVec2::new(x, y), false);
+        }
+    }
+}
+
+/// Fill every floor region except the largest one with walls.
+fn keep_largest_region(grid: &mut Grid) {
+    let mut largest: Option<Reachable> = None;
+    let mut visited = vec![false; grid.walls.len()];
+    for position in grid.floors() {
+        if visited[grid.index(position)] {
+            continue;
+        }
+        let region = flood_fill(grid.size, position, |p| !grid.is_wall(p));
+        for (seen, reached) in visited.iter_mut().zip(&region.tiles) {
+            *seen |= reached;
+        }
+        if largest.as_ref().is_none_or(|l| region.count() > l.count()) {
+            largest = Some(region);
+        }
+    }
+
+    let Some(largest) = largest else {
+        return;
+    };
+    for position in grid.positions().collect::<Vec<_>>() {
+        if !largest.contains(position) {
+            grid.set_wall(position, true);
+        }
+    }
+}
+
+/// Pick spawn positions on the floor and build the level,
+/// or return `None` if the spawns don't fit.
+fn place_spawns(
+    rng: &mut StdRng,
+    grid: &Grid,
+    seed: u64,
+    settings: &GeneratorSettings,
+) -> Option<LevelDef> {
+    let mut floors = grid.floors();
+    // Require a reasonably large arena.
+    if floors.len() < (grid.size.x * grid.size.y / 4) as usize {
+        return None;
+    }
+    floors.shuffle(rng);
+
+    // Prefer open spaces for the player.
+    let player_spawn = floors
+        .iter()
+        .copied()
+        .find(|&p| grid.wall_neighbors(p) == 0)
+        .or_else(|| floors.first().copied())?;
+    let mut taken = vec![player_spawn];
+
+    let mut pick = |count: u32, min_distance: f32| -> Option<Vec<UVec2>> {
+        let picked: Vec<UVec2> = floors
+            .iter()
+            .copied()
+            .filter(|p| p.as_vec2().distance(player_spawn.as_vec2()) >= min_distance)
+            .filter(|p| !taken.contains(p))
+            .take(count as usize)
+            .collect();
+        taken.extend(&picked);
+        (picked.len() == count as usize).then_some(picked)
+    };
+    let enemy_spawners = pick(settings.enemy_spawners, settings.min_enemy_distance)?;
+    let pickups = pick(settings.pickups, settings.min_pickup_distance)?;
+
+    let tiles = (0..grid.size.y)
+        .map(|y| {
+            (0..grid.size.x)
+                .map(|x| {
+                    let tile = if grid.is_wall(UVec2::new(x, y)) {
+                        TileId::WALL
+                    } else {
+                        TileId::FLOOR
+                    };
+                    tile.properties().symbol
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(LevelDef {
+        name: format!("Arena #{seed}"),
+        tile_size: settings.tile_size,
+        tiles,
+        player_spawn,
+        enemy_spawners: enemy_spawners
+            .into_iter()
+            .map(|position| EnemySpawnerDef {
+                position,
+                interval_secs: 5.0,
+                components: Vec::new(),
+            })
+            .collect(),
+        pickups: pickups
+            .into_iter()
+            .map(|position| PickupDef {
+                kind: PickupKind::Egg,
+                position,
+                components: Vec::new(),
+            })
+            .collect(),
+        checkpoints: Vec::new(),
+        triggers: Vec::new(),
+        doors: Vec::new(),
+        wires: Vec::new(),
+        components: default(),
+        exit: None,
+        boss: None,
+        objectives: settings.objectives.clone(),
+        time_limit_secs: None,
+        par_time_secs: 60.0,
+        soundtrack: settings.soundtrack,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::ron;
+
+    use super::*;
+
+    #[test]
+    fn same_seed_generates_same_level() {
+        let rooms = GeneratorSettings {
+            layout: Layout::Rooms {
+                max_rooms: 8,
+                min_room_size: UVec2::new(4, 3),
+                max_room_size: UVec2::new(10, 7),
+            },
+            ..default()
+        };
+        for settings in [GeneratorSettings::default(), rooms] {
+            for seed in [0, 1, 42] {
+                let first = generate(seed, &settings).unwrap();
+                let second = generate(seed, &settings).unwrap();
+                assert_eq!(
+                    ron::ser::to_string(&first).unwrap(),
+                    ron::ser::to_string(&second).unwrap(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        let tiny = GeneratorSettings {
+            size: UVec2::new(2, 0),
+            ..default()
+        };
+        assert!(matches!(
+            generate(0, &tiny),
+            Err(GenerateError::InvalidSettings(_))
+        ));
+
+        let inverted_rooms = GeneratorSettings {
+            layout: Layout::Rooms {
+                max_rooms: 4,
+                min_room_size: UVec2::new(8, 8),
+                max_room_size: UVec2::new(4, 4),
+            },
+            ..default()
+        };
+        assert!(matches!(
+            generate(0, &inverted_rooms),
+            Err(GenerateError::InvalidSettings(_))
+        ));
+    }
+}
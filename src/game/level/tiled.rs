@@ -0,0 +1,637 @@
+//! Load [`LevelDef`]s from maps made with [Tiled](https://www.mapeditor.org/),
+//! saved in its JSON format (`.tmj`).
+//!
+//! - Tile layers are merged from bottom to top. Tilesets have to be made from
+//!   `images/tileset.png`, so that tile IDs match our tileset's atlas indices.
+//!   Conveyors point right; flip or rotate them to change their direction.
+//! - Objects are converted by their type (or class) to the matching level object:
+//!   `player_spawn`, `enemy_spawner`, `pickup`, `checkpoint`, `exit`, `boss`, `door` or `trigger`.
+//!   Their position is the tile containing the object's center, except for triggers,
+//!   which cover all tiles of the object's rectangle. Doors and triggers are named after
+//!   their object.
+//! - Custom properties of class type are converted to [`ComponentDef`]s for their object,
+//!   with the property's class naming the component, e.g. `Movement`.
+//! - Map properties set the level's `name`, `objectives`, `wires` and `soundtrack` (in RON syntax),
+//!   `time_limit_secs`, `par_time_secs` and `tile_size`.
+
+use std::{collections::BTreeMap, error::Error, fmt};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Deserialize,
+};
+use serde_json::Value;
+
+use super::{
+    components::{ComponentDef, PropertyValue},
+    loader::LevelLoaderError,
+    CheckpointDef, EnemySpawnerDef, LevelDef, PickupDef, PickupKind,
+};
+use crate::game::{
+    assets::SoundtrackKey,
+    door::DoorDef,
+    tilemap::{tileset::Surface, TileId},
+    triggers::{TriggerDef, TriggerKind},
+};
+
+#[derive(Default)]
+pub struct TiledMapLoader;
+
+impl AssetLoader for TiledMapLoader {
+    type Asset = LevelDef;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let map = serde_json::from_slice::<TiledMap>(&bytes)?;
+        let default_name = load_context
+            .path()
+            .file_stem()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let level = map.into_level(default_name)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmj"]
+    }
+}
+
+/// Tiled stores whether a tile is flipped in the highest bits of its global ID.
+const FLIP_FLAGS: u32 = 0xf000_0000;
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+
+/// World size of a tile, unless the map overrides it with a `tile_size` property.
+const DEFAULT_TILE_SIZE: f32 = 64.0;
+
+/// Seconds between two chasers of an `enemy_spawner` without an `interval_secs` property.
+const DEFAULT_SPAWNER_INTERVAL_SECS: f32 = 5.0;
+
+#[derive(Deserialize)]
+struct TiledMap {
+    width: u32,
+    height: u32,
+    #[serde(rename = "tilewidth")]
+    tile_width: f32,
+    #[serde(rename = "tileheight")]
+    tile_height: f32,
+    #[serde(default)]
+    infinite: bool,
+    layers: Vec<TiledLayer>,
+    tilesets: Vec<TiledTilesetRef>,
+    #[serde(default)]
+    properties: Vec<TiledProperty>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum TiledLayer {
+    #[serde(rename = "tilelayer")]
+    Tiles {
+        name: String,
+        data: TiledLayerData,
+    },
+    #[serde(rename = "objectgroup")]
+    Objects {
+        objects: Vec<TiledObject>,
+    },
+    Group {
+        layers: Vec<TiledLayer>,
+    },
+    /// Image layers are only useful for reference while editing.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TiledLayerData {
+    Csv(Vec<u32>),
+    /// Base64 encoded and optionally compressed data, which we don't support.
+    Encoded(IgnoredAny),
+}
+
+#[derive(Deserialize)]
+struct TiledTilesetRef {
+    #[serde(rename = "firstgid")]
+    first_gid: u32,
+}
+
+#[derive(Deserialize)]
+struct TiledObject {
+    #[serde(default)]
+    name: String,
+    /// Called `class` by Tiled 1.9.
+    #[serde(default, alias = "class")]
+    r#type: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    /// Set for tile objects, whose position is their bottom left corner.
+    #[serde(default)]
+    gid: Option<u32>,
+    #[serde(default)]
+    properties: Vec<TiledProperty>,
+}
+
+#[derive(Deserialize)]
+struct TiledProperty {
+    name: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    /// The class of `class` properties.
+    #[serde(rename = "propertytype", default)]
+    class: String,
+    value: Value,
+}
+
+impl TiledMap {
+    fn into_level(self, default_name: String) -> Result<LevelDef, TiledMapError> {
+        if self.infinite {
+            return Err(TiledMapError::InfiniteMap);
+        }
+        let size = UVec2::new(self.width, self.height);
+        let mut tiles = vec![TileId::EMPTY; (size.x * size.y) as usize];
+        let mut objects = Vec::new();
+        self.collect_layers(&self.layers, &mut tiles, &mut objects)?;
+
+        let mut properties = Properties::new(&self.properties, "map");
+        let mut level = LevelDef {
+            name: properties.take("name")?.unwrap_or(default_name),
+            tile_size: properties.take("tile_size")?.unwrap_or(DEFAULT_TILE_SIZE),
+            tiles: tiles
+                .chunks(size.x as usize)
+                .map(|row| row.iter().map(|tile| tile.properties().symbol).collect())
+                .collect(),
+            player_spawn: UVec2::ZERO,
+            enemy_spawners: Vec::new(),
+            pickups: Vec::new(),
+            exit: None,
+            checkpoints: Vec::new(),
+            boss: None,
+            triggers: Vec::new(),
+            doors: Vec::new(),
+            wires: properties.take_ron("wires")?.unwrap_or_default(),
+            components: default(),
+            objectives: properties
+                .take_ron("objectives")?
+                .ok_or(TiledMapError::MissingProperty("objectives"))?,
+            time_limit_secs: properties.take("time_limit_secs")?,
+            par_time_secs: properties.take("par_time_secs")?.unwrap_or(60.0),
+            soundtrack: properties
+                .take_ron("soundtrack")?
+                .unwrap_or(SoundtrackKey::Gameplay),
+        };
+        properties.finish()?;
+
+        let mut player_spawn = None;
+        for object in objects {
+            let position = self.object_tile(object)?;
+            let mut properties = Properties::new(&object.properties, &object.name);
+            // Components belong to this object only, not to others on the same tile.
+            let components = properties.take_components()?;
+            match object.r#type.as_str() {
+                "player_spawn" => {
+                    player_spawn = Some(position);
+                    level.components.player_spawn = components;
+                }
+                "enemy_spawner" => level.enemy_spawners.push(EnemySpawnerDef {
+                    position,
+                    interval_secs: properties
+                        .take("interval_secs")?
+                        .unwrap_or(DEFAULT_SPAWNER_INTERVAL_SECS),
+                    components,
+                }),
+                "pickup" => level.pickups.push(PickupDef {
+                    kind: properties.take_ron("kind")?.unwrap_or(PickupKind::Egg),
+                    position,
+                    components,
+                }),
+                "checkpoint" => level.checkpoints.push(CheckpointDef {
+                    position,
+                    components,
+                }),
+                "exit" => {
+                    level.exit = Some(position);
+                    level.components.exit = components;
+                }
+                "boss" => {
+                    level.boss = Some(position);
+                    level.components.boss = components;
+                }
+                "door" => level.doors.push(DoorDef {
+                    name: object.name.clone(),
+                    position,
+                    locked: properties.take("locked")?.unwrap_or_default(),
+                    components,
+                }),
+                "trigger" => {
+                    let (position, size) = self.object_area(object)?;
+                    level.triggers.push(TriggerDef {
+                        name: object.name.clone(),
+                        position,
+                        size,
+                        kind: properties.take_ron("kind")?.unwrap_or(TriggerKind::Volume),
+                        components,
+                    });
+                }
+                _ => {
+                    return Err(TiledMapError::UnknownObjectType {
+                        object: object.name.clone(),
+                        kind: object.r#type.clone(),
+                    })
+                }
+            }
+            properties.finish()?;
+        }
+        level.player_spawn = player_spawn.ok_or(TiledMapError::MissingPlayerSpawn)?;
+
+        Ok(level)
+    }
+
+    /// Merge tile layers into `tiles` and gather the objects of all object layers.
+    fn collect_layers<'a>(
+        &self,
+        layers: &'a [TiledLayer],
+        tiles: &mut [TileId],
+        objects: &mut Vec<&'a TiledObject>,
+    ) -> Result<(), TiledMapError> {
+        for layer in layers {
+            match layer {
+                TiledLayer::Tiles { name, data } => {
+                    let TiledLayerData::Csv(data) = data else {
+                        return Err(TiledMapError::EncodedLayer(name.clone()));
+                    };
+                    if data.len() != tiles.len() {
+                        return Err(TiledMapError::LayerSize(name.clone()));
+                    }
+                    for (tile, &gid) in tiles.iter_mut().zip(data) {
+                        if gid & !FLIP_FLAGS != 0 {
+                            *tile = self.tile_id(gid & !FLIP_FLAGS)?;
+                            *tile = orient_conveyor(*tile, gid);
+                        }
+                    }
+                }
+                TiledLayer::Objects { objects: layer } => objects.extend(layer),
+                TiledLayer::Group { layers } => self.collect_layers(layers, tiles, objects)?,
+                TiledLayer::Other => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Look up the tile type drawn by the tile with the global ID `gid`.
+    fn tile_id(&self, gid: u32) -> Result<TileId, TiledMapError> {
+        let first_gid = self
+            .tilesets
+            .iter()
+            .map(|tileset| tileset.first_gid)
+            .filter(|first_gid| *first_gid <= gid)
+            .max()
+            .ok_or(TiledMapError::UnknownTile(gid))?;
+        let atlas_index = (gid - first_gid) as usize;
+        TileId::all()
+            .find(|tile| tile.properties().atlas_index == Some(atlas_index))
+            .ok_or(TiledMapError::UnknownTile(gid))
+    }
+
+    /// Return the tile containing the center of `object`.
+    fn object_tile(&self, object: &TiledObject) -> Result<UVec2, TiledMapError> {
+        let center = object.top_left() + object.size() / 2.0;
+        self.pixel_to_tile(object, center.floor())
+    }
+
+    /// Return the top left tile and the size in tiles of the tiles covered by `object`.
+    fn object_area(&self, object: &TiledObject) -> Result<(UVec2, UVec2), TiledMapError> {
+        let top_left = self.pixel_to_tile(object, object.top_left())?;
+        let tile_size = Vec2::new(self.tile_width, self.tile_height);
+        let size = (object.size() / tile_size)
+            .round()
+            .as_uvec2()
+            .max(UVec2::ONE);
+        Ok((top_left, size))
+    }
+
+    fn pixel_to_tile(&self, object: &TiledObject, pixel: Vec2) -> Result<UVec2, TiledMapError> {
+        let tile = (pixel / Vec2::new(self.tile_width, self.tile_height)).floor();
+        if tile.cmplt(Vec2::ZERO).any() {
+            return Err(TiledMapError::ObjectOutOfBounds(object.name.clone()));
+        }
+        Ok(tile.as_uvec2())
+    }
+}
+
+impl TiledObject {
+    fn top_left(&self) -> Vec2 {
+        let top = if self.gid.is_some() {
+            self.y - self.height
+        } else {
+            self.y
+        };
+        Vec2::new(self.x, top)
+    }
+
+    fn size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height)
+    }
+}
+
+/// Custom properties of a map or object. Every property has to be used exactly once,
+/// so that typos are reported instead of silently ignored.
+struct Properties<'a> {
+    properties: Vec<&'a TiledProperty>,
+    /// The map or object the properties belong to, for error messages.
+    owner: &'a str,
+}
+
+impl<'a> Properties<'a> {
+    fn new(properties: &'a [TiledProperty], owner: &'a str) -> Self {
+        Self {
+            properties: properties.iter().collect(),
+            owner,
+        }
+    }
+
+    fn take_property(&mut self, name: &str) -> Option<&'a TiledProperty> {
+        let index = self
+            .properties
+            .iter()
+            .position(|property| property.name == name)?;
+        Some(self.properties.remove(index))
+    }
+
+    /// Take a property holding a plain value, like a number.
+    fn take<T: DeserializeOwned>(&mut self, name: &str) -> Result<Option<T>, TiledMapError> {
+        self.take_property(name)
+            .map(|property| {
+                serde_json::from_value(property.value.clone())
+                    .map_err(|error| self.invalid(&property.name, error))
+            })
+            .transpose()
+    }
+
+    /// Take a string property holding a value in RON syntax, like `CollectAll(Egg)`.
+    fn take_ron<T: DeserializeOwned>(&mut self, name: &str) -> Result<Option<T>, TiledMapError> {
+        let Some(value) = self.take::<String>(name)? else {
+            return Ok(None);
+        };
+        ron::de::from_str(&value)
+            .map(Some)
+            .map_err(|error| self.invalid(name, error))
+    }
+
+    /// Take all properties of class type and convert them to components.
+    fn take_components(&mut self) -> Result<Vec<ComponentDef>, TiledMapError> {
+        let (classes, rest) = self
+            .properties
+            .iter()
+            .partition(|property| property.kind == "class");
+        self.properties = rest;
+
+        classes
+            .into_iter()
+            .map(|property: &TiledProperty| {
+                let mut fields = BTreeMap::new();
+                flatten_fields(&property.value, String::new(), &mut fields)
+                    .map_err(|error| self.invalid(&property.name, error))?;
+                Ok(ComponentDef {
+                    component: property.class.clone(),
+                    fields,
+                })
+            })
+            .collect()
+    }
+
+    /// Check that all properties were used.
+    fn finish(self) -> Result<(), TiledMapError> {
+        match self.properties.first() {
+            Some(property) => Err(self.invalid(&property.name, "unknown property")),
+            None => Ok(()),
+        }
+    }
+
+    fn invalid(&self, name: &str, error: impl fmt::Display) -> TiledMapError {
+        TiledMapError::InvalidProperty {
+            owner: self.owner.to_string(),
+            name: name.to_string(),
+            reason: error.to_string(),
+        }
+    }
+}
+
+/// Convert the members of a class property to field values by their reflection path.
+/// Nested classes become nested paths like `timer.duration`.
+fn flatten_fields(
+    value: &Value,
+    path: String,
+    fields: &mut BTreeMap<String, PropertyValue>,
+) -> Result<(), String> {
+    let value = match value {
+        Value::Object(members) => {
+            for (name, value) in members {
+                let path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                flatten_fields(value, path, fields)?;
+            }
+            return Ok(());
+        }
+        Value::Bool(value) => PropertyValue::Bool(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) => PropertyValue::Int(value),
+            None => PropertyValue::Float(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => PropertyValue::String(value.clone()),
+        Value::Null | Value::Array(_) => {
+            return Err(format!("member {path:?} has an unsupported type"));
+        }
+    };
+    fields.insert(path, value);
+    Ok(())
+}
+
+/// An error describing why a Tiled map can't be converted to a [`LevelDef`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledMapError {
+    /// Infinite maps have no fixed size.
+    InfiniteMap,
+    /// A tile layer does not use the CSV layer format.
+    EncodedLayer(String),
+    /// A tile layer does not have the same size as the map.
+    LayerSize(String),
+    /// A tile ID that does not belong to our tileset.
+    UnknownTile(u32),
+    /// An object lies outside of the map.
+    ObjectOutOfBounds(String),
+    UnknownObjectType {
+        object: String,
+        kind: String,
+    },
+    MissingPlayerSpawn,
+    MissingProperty(&'static str),
+    InvalidProperty {
+        owner: String,
+        name: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for TiledMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InfiniteMap => write!(f, "infinite maps are not supported"),
+            Self::EncodedLayer(layer) => {
+                write!(f, "layer {layer:?} has to use the CSV layer format")
+            }
+            Self::LayerSize(layer) => {
+                write!(f, "layer {layer:?} does not have the same size as the map")
+            }
+            Self::UnknownTile(gid) => write!(f, "tile {gid} is not part of the tileset"),
+            Self::ObjectOutOfBounds(object) => {
+                write!(f, "object {object:?} is outside of the map")
+            }
+            Self::UnknownObjectType { object, kind } => {
+                write!(f, "object {object:?} has unknown type {kind:?}")
+            }
+            Self::MissingPlayerSpawn => write!(f, "map has no player_spawn object"),
+            Self::MissingProperty(name) => write!(f, "map has no {name:?} property"),
+            Self::InvalidProperty {
+                owner,
+                name,
+                reason,
+            } => write!(f, "property {name:?} of {owner:?} is invalid: {reason}"),
+        }
+    }
+}
+
+impl Error for TiledMapError {}
+
+/// Conveyors only have one tile in the tileset, so their direction comes from how
+/// the tile is flipped and rotated in Tiled.
+fn orient_conveyor(tile: TileId, gid: u32) -> TileId {
+    let Surface::Conveyor { mut direction } = tile.properties().surface else {
+        return tile;
+    };
+    // Tiled applies the diagonal flip first, then the horizontal and vertical ones.
+    if gid & FLIPPED_DIAGONALLY != 0 {
+        direction = IVec2::new(direction.y, direction.x);
+    }
+    if gid & FLIPPED_HORIZONTALLY != 0 {
+        direction.x = -direction.x;
+    }
+    if gid & FLIPPED_VERTICALLY != 0 {
+        direction.y = -direction.y;
+    }
+    TileId::CONVEYORS
+        .into_iter()
+        .find(|conveyor| conveyor.properties().surface == Surface::Conveyor { direction })
+        .unwrap_or(tile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<LevelDef, TiledMapError> {
+        serde_json::from_str::<TiledMap>(text)
+            .unwrap()
+            .into_level("test".to_string())
+    }
+
+    /// A 2×1 map with `objects` and the given map properties.
+    fn map(objects: &str, properties: &str) -> String {
+        format!(
+            r#"{{
+                "width": 2, "height": 1, "tilewidth": 16, "tileheight": 16,
+                "tilesets": [{{"firstgid": 1}}],
+                "layers": [
+                    {{"type": "tilelayer", "name": "ground", "data": [0, 0]}},
+                    {{"type": "objectgroup", "objects": [{objects}]}}
+                ],
+                "properties": [{properties}]
+            }}"#
+        )
+    }
+
+    const OBJECTIVES: &str =
+        r#"{"name": "objectives", "type": "string", "value": "[Survive(secs: 10.0)]"}"#;
+    const PLAYER_SPAWN: &str =
+        r#"{"name": "spawn", "type": "player_spawn", "x": 20, "y": 0, "width": 8, "height": 8}"#;
+
+    #[test]
+    fn shipped_map_is_valid() {
+        let level = parse(include_str!("../../../assets/levels/pits.tmj")).unwrap();
+        assert_eq!(level.validate(), Ok(()));
+    }
+
+    #[test]
+    fn objects_keep_their_own_components() {
+        let pickup = r#"{
+            "type": "pickup", "x": 0, "y": 0, "width": 8, "height": 8,
+            "properties": [
+                {"name": "kind", "type": "string", "value": "Key"},
+                {"name": "bob", "type": "class", "propertytype": "Bob", "value": {"height": 4}}
+            ]
+        }"#;
+        let level = parse(&map(&format!("{PLAYER_SPAWN}, {pickup}"), OBJECTIVES)).unwrap();
+        assert_eq!(level.player_spawn, UVec2::new(1, 0));
+        assert!(level.components.player_spawn.is_empty());
+        let [pickup] = &level.pickups[..] else {
+            panic!("expected one pickup");
+        };
+        assert_eq!(pickup.kind, PickupKind::Key);
+        assert_eq!(
+            pickup.components,
+            [ComponentDef {
+                component: "Bob".to_string(),
+                fields: BTreeMap::from([("height".to_string(), PropertyValue::Int(4))]),
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_maps_are_rejected() {
+        assert_eq!(
+            parse(&map("", OBJECTIVES)).unwrap_err(),
+            TiledMapError::MissingPlayerSpawn
+        );
+        assert_eq!(
+            parse(&map(PLAYER_SPAWN, "")).unwrap_err(),
+            TiledMapError::MissingProperty("objectives")
+        );
+
+        let unknown_type = r#"{"name": "lamp", "type": "lamp", "x": 0, "y": 0}"#;
+        assert_eq!(
+            parse(&map(&format!("{PLAYER_SPAWN}, {unknown_type}"), OBJECTIVES)).unwrap_err(),
+            TiledMapError::UnknownObjectType {
+                object: "lamp".to_string(),
+                kind: "lamp".to_string(),
+            }
+        );
+
+        let typo = r#"{"name": "objectivs", "type": "string", "value": "[]"}"#;
+        assert!(matches!(
+            parse(&map(PLAYER_SPAWN, &format!("{OBJECTIVES}, {typo}"))),
+            Err(TiledMapError::InvalidProperty { name, .. }) if name == "objectivs"
+        ));
+    }
+}
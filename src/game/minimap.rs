@@ -0,0 +1,379 @@
+//! A minimap of the level and arrows at the edge of the screen pointing at off-screen threats.
+//! Entities show up on both by opting in with a [`MapIcon`].
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+    window::PrimaryWindow,
+};
+
+use super::{
+    camera::{resolution::VirtualResolution, split::SplitViewport, CameraFollow},
+    tilemap::{TileId, Tilemap},
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<MapIcon>();
+    app.add_systems(OnEnter(Screen::Playing), spawn_minimap);
+    app.add_systems(
+        Update,
+        (
+            draw_minimap_tiles,
+            (sync_icon_nodes::<MinimapIcon>, place_minimap_icons).chain(),
+            (sync_icon_nodes::<ThreatIndicator>, place_threat_indicators).chain(),
+        )
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Shows an entity on the minimap. Threats also get an arrow at the edge of the
+/// screen while they are out of view.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct MapIcon {
+    pub color: Color,
+    /// Width and height of the icon on the minimap, in pixels.
+    pub size: f32,
+    pub threat: bool,
+}
+
+impl Default for MapIcon {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            size: 6.0,
+            threat: false,
+        }
+    }
+}
+
+impl MapIcon {
+    pub fn new(color: Color) -> Self {
+        Self { color, ..default() }
+    }
+
+    pub fn with_size(self, size: f32) -> Self {
+        Self { size, ..self }
+    }
+
+    pub fn threat(self) -> Self {
+        Self {
+            threat: true,
+            ..self
+        }
+    }
+}
+
+/// Width of the minimap in pixels. Its height follows the level's aspect ratio.
+const MINIMAP_WIDTH: f32 = 200.0;
+const WALL_COLOR: [u8; 4] = [40, 40, 60, 230];
+const FLOOR_COLOR: [u8; 4] = [120, 120, 140, 160];
+const HAZARD_COLOR: [u8; 4] = [160, 60, 60, 200];
+
+/// The minimap node. Its image shows one pixel per tile.
+#[derive(Component)]
+struct Minimap;
+
+/// Full-screen node holding the [`ThreatIndicator`]s.
+#[derive(Component)]
+struct ThreatIndicators;
+
+/// A node showing the [`MapIcon`] of an entity.
+trait IconNode: Component {
+    /// The node to add these nodes to.
+    type Parent: Component;
+
+    fn new(entity: Entity) -> Self;
+    fn entity(&self) -> Entity;
+    /// Whether `icon` is shown by this kind of node.
+    fn shows(icon: &MapIcon) -> bool;
+    fn spawn(commands: &mut Commands, icon: &MapIcon) -> Entity;
+}
+
+/// The node of an icon on the minimap.
+#[derive(Component)]
+struct MinimapIcon(Entity);
+
+impl IconNode for MinimapIcon {
+    type Parent = Minimap;
+
+    fn new(entity: Entity) -> Self {
+        Self(entity)
+    }
+
+    fn entity(&self) -> Entity {
+        self.0
+    }
+
+    fn shows(_icon: &MapIcon) -> bool {
+        true
+    }
+
+    fn spawn(commands: &mut Commands, icon: &MapIcon) -> Entity {
+        commands
+            .spawn((
+                Name::new("Minimap Icon"),
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(icon.size),
+                        height: Val::Px(icon.size),
+                        margin: UiRect {
+                            left: Val::Px(-icon.size / 2.0),
+                            top: Val::Px(-icon.size / 2.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    background_color: icon.color.into(),
+                    ..default()
+                },
+            ))
+            .id()
+    }
+}
+
+/// An arrow pointing at an off-screen threat.
+#[derive(Component)]
+struct ThreatIndicator(Entity);
+
+impl IconNode for ThreatIndicator {
+    type Parent = ThreatIndicators;
+
+    fn new(entity: Entity) -> Self {
+        Self(entity)
+    }
+
+    fn entity(&self) -> Entity {
+        self.0
+    }
+
+    fn shows(icon: &MapIcon) -> bool {
+        icon.threat
+    }
+
+    fn spawn(commands: &mut Commands, icon: &MapIcon) -> Entity {
+        commands
+            .spawn((
+                Name::new("Threat Indicator"),
+                TextBundle::from_section(
+                    ">",
+                    TextStyle {
+                        font_size: INDICATOR_FONT_SIZE,
+                        color: icon.color,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                }),
+            ))
+            .id()
+    }
+}
+
+fn spawn_minimap(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Minimap"),
+        Minimap,
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                right: Val::Px(12.0),
+                width: Val::Px(MINIMAP_WIDTH),
+                ..default()
+            },
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+    commands.spawn((
+        Name::new("Threat Indicators"),
+        ThreatIndicators,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+}
+
+/// Redraw the minimap's image whenever the tilemap changes, e.g. when a door opens.
+fn draw_minimap_tiles(
+    tilemap_query: Query<&Tilemap, Changed<Tilemap>>,
+    mut minimap_query: Query<(&mut UiImage, &mut Style), With<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(tilemap) = tilemap_query.iter().next() else {
+        return;
+    };
+    let size = tilemap.size();
+    let data = tilemap
+        .iter()
+        .flat_map(|(_, tile)| {
+            let properties = tile.properties();
+            if tile == TileId::EMPTY {
+                [0; 4]
+            } else if properties.solid {
+                WALL_COLOR
+            } else if properties.hazard.is_some() {
+                HAZARD_COLOR
+            } else {
+                FLOOR_COLOR
+            }
+        })
+        .collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    let texture = images.add(image);
+
+    for (mut ui_image, mut style) in &mut minimap_query {
+        ui_image.texture = texture.clone();
+        style.height = Val::Px(MINIMAP_WIDTH * size.y as f32 / size.x.max(1) as f32);
+    }
+}
+
+/// Spawn a node for every [`MapIcon`] shown by `T`, and despawn nodes of removed icons.
+fn sync_icon_nodes<T: IconNode>(
+    mut commands: Commands,
+    icon_query: Query<(Entity, &MapIcon), Added<MapIcon>>,
+    node_query: Query<(Entity, &T)>,
+    all_icons: Query<(), With<MapIcon>>,
+    parent_query: Query<Entity, With<T::Parent>>,
+) {
+    let Ok(parent) = parent_query.get_single() else {
+        return;
+    };
+    for (entity, icon) in &icon_query {
+        if T::shows(icon) {
+            let node = T::spawn(&mut commands, icon);
+            commands
+                .entity(node)
+                .insert(T::new(entity))
+                .set_parent(parent);
+        }
+    }
+    for (node, icon_node) in &node_query {
+        if !all_icons.contains(icon_node.entity()) {
+            commands.entity(node).despawn_recursive();
+        }
+    }
+}
+
+fn place_minimap_icons(
+    tilemap_query: Query<(&Tilemap, &GlobalTransform)>,
+    icon_query: Query<&GlobalTransform, With<MapIcon>>,
+    mut node_query: Query<(&MinimapIcon, &mut Style)>,
+) {
+    let Some((tilemap, tilemap_transform)) = tilemap_query.iter().next() else {
+        return;
+    };
+    let level_size = tilemap.size().as_vec2() * tilemap.tile_size();
+    let to_local = tilemap_transform.affine().inverse();
+    for (icon_node, mut style) in &mut node_query {
+        let Ok(transform) = icon_query.get(icon_node.0) else {
+            continue;
+        };
+        let local = to_local.transform_point3(transform.translation()).xy();
+        let fraction = Vec2::new(local.x, -local.y) / level_size;
+        style.left = Val::Percent(fraction.x * 100.0);
+        style.top = Val::Percent(fraction.y * 100.0);
+    }
+}
+
+const INDICATOR_FONT_SIZE: f32 = 32.0;
+/// Threats further away than this get the smallest arrows.
+const INDICATOR_FAR_DISTANCE: f32 = 2000.0;
+/// How far from the edge of the view to place arrows, as a fraction of the view.
+const INDICATOR_EDGE_INSET: f32 = 0.9;
+
+fn place_threat_indicators(
+    resolution: Res<VirtualResolution>,
+    ui_scale: Res<UiScale>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform, &SplitViewport), With<CameraFollow>>,
+    icon_query: Query<&GlobalTransform, With<MapIcon>>,
+    mut node_query: Query<(
+        &ThreatIndicator,
+        &mut Style,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    // Arrows are placed relative to the first player's view.
+    let Some((camera, camera_transform)) = camera_query
+        .iter()
+        .find(|(camera, _, viewport)| camera.is_active && viewport.index == 0)
+        .map(|(camera, transform, _)| (camera, transform))
+    else {
+        return;
+    };
+    let (Ok(window), Some(viewport)) = (window_query.get_single(), camera.physical_viewport_rect())
+    else {
+        return;
+    };
+    let physical_to_ui = window.scale_factor() * ui_scale.0;
+    let viewport_size = viewport.size().as_vec2();
+    let camera_position = camera_transform.translation().xy();
+
+    for (indicator, mut style, mut transform, mut visibility) in &mut node_query {
+        let Some(ndc) = icon_query
+            .get(indicator.0)
+            .ok()
+            .and_then(|icon| camera.world_to_ndc(camera_transform, icon.translation()))
+        else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let direction = ndc.xy();
+        if direction.abs().max_element() <= 1.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+
+        // Move the arrow along the line from the center of the view to the threat,
+        // until it touches the edge of the view.
+        let edge = direction / direction.abs().max_element() * INDICATOR_EDGE_INSET;
+        let physical =
+            viewport.min.as_vec2() + Vec2::new(edge.x + 1.0, 1.0 - edge.y) / 2.0 * viewport_size;
+        let position = (physical - resolution.area.min.as_vec2()) / physical_to_ui;
+        style.left = Val::Px(position.x);
+        style.top = Val::Px(position.y);
+
+        // The UI's y axis points down, so turn the other way.
+        let angle = (direction * viewport_size).to_angle();
+        transform.rotation = Quat::from_rotation_z(-angle);
+
+        let distance = icon_query
+            .get(indicator.0)
+            .map_or(INDICATOR_FAR_DISTANCE, |icon| {
+                icon.translation().xy().distance(camera_position)
+            });
+        let closeness = 1.0 - (distance / INDICATOR_FAR_DISTANCE).min(1.0);
+        transform.scale = Vec3::splat(0.5 + closeness);
+    }
+}
@@ -5,15 +5,56 @@ use bevy::prelude::*;
 mod animation;
 pub mod assets;
 pub mod audio;
+pub mod camera;
+pub mod chaser;
+mod checkpoint;
+pub mod door;
+mod hazards;
+pub mod health;
+mod hit_stop;
+mod juice;
+pub mod layers;
+pub mod level;
+pub mod minimap;
 mod movement;
+pub mod objectives;
+pub mod particles;
+mod persist;
+pub mod pickup;
+pub mod progress;
+pub mod settings;
 pub mod spawn;
+pub mod tilemap;
+pub mod triggers;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         animation::plugin,
         audio::plugin,
+        camera::plugin,
+        chaser::plugin,
+        checkpoint::plugin,
+        door::plugin,
+        hazards::plugin,
+        health::plugin,
+        hit_stop::plugin,
+        // Register level and campaign assets before `assets::plugin` starts loading them.
+        level::plugin,
         assets::plugin,
+        minimap::plugin,
         movement::plugin,
+        objectives::plugin,
+        pickup::plugin,
+    ));
+    // `add_plugins` takes at most 15 plugins at once.
+    app.add_plugins((
+        juice::plugin,
+        layers::plugin,
+        particles::plugin,
+        progress::plugin,
+        settings::plugin,
         spawn::plugin,
+        tilemap::plugin,
+        triggers::plugin,
     ));
 }
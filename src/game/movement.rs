@@ -3,8 +3,9 @@
 //! If you want to move the player in a smoother way,
 //! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/latest/examples/movement/physics_in_fixed_timestep.rs).
 
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
+use super::spawn::player::{Player, PlayerControls};
 use crate::AppSet;
 
 pub(super) fn plugin(app: &mut App) {
@@ -16,12 +17,10 @@ pub(super) fn plugin(app: &mut App) {
     );
 
     // Apply movement based on controls.
-    app.register_type::<(Movement, WrapWithinWindow)>();
+    app.register_type::<(Movement, MovementModifiers, Facing)>();
     app.add_systems(
         Update,
-        (apply_movement, wrap_within_window)
-            .chain()
-            .in_set(AppSet::Update),
+        (apply_movement, update_facing).in_set(AppSet::Update),
     );
 }
 
@@ -31,66 +30,135 @@ pub struct MovementController(pub Vec2);
 
 fn record_movement_controller(
     input: Res<ButtonInput<KeyCode>>,
-    mut controller_query: Query<&mut MovementController>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut controller_query: Query<(&mut MovementController, &PlayerControls), With<Player>>,
 ) {
-    // Collect directional input.
-    let mut intent = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
-        intent.y += 1.0;
-    }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
-        intent.y -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
-        intent.x -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
-        intent.x += 1.0;
+    // `Gamepads` has no order, so sort them to give every player the same gamepad each frame.
+    let mut gamepads: Vec<Gamepad> = gamepads.iter().collect();
+    gamepads.sort_by_key(|gamepad| gamepad.id);
+
+    for (mut controller, controls) in &mut controller_query {
+        // Collect directional input.
+        let mut intent = Vec2::ZERO;
+        match *controls {
+            PlayerControls::Keyboard | PlayerControls::Wasd | PlayerControls::Arrows => {
+                let wasd = *controls != PlayerControls::Arrows;
+                let arrows = *controls != PlayerControls::Wasd;
+                let pressed = |letter, arrow| {
+                    (wasd && input.pressed(letter)) || (arrows && input.pressed(arrow))
+                };
+                if pressed(KeyCode::KeyW, KeyCode::ArrowUp) {
+                    intent.y += 1.0;
+                }
+                if pressed(KeyCode::KeyS, KeyCode::ArrowDown) {
+                    intent.y -= 1.0;
+                }
+                if pressed(KeyCode::KeyA, KeyCode::ArrowLeft) {
+                    intent.x -= 1.0;
+                }
+                if pressed(KeyCode::KeyD, KeyCode::ArrowRight) {
+                    intent.x += 1.0;
+                }
+            }
+            PlayerControls::Gamepad(index) => {
+                if let Some(&gamepad) = gamepads.get(index) {
+                    let axis = |axis_type| {
+                        gamepad_axes
+                            .get(GamepadAxis::new(gamepad, axis_type))
+                            .unwrap_or_default()
+                    };
+                    intent = Vec2::new(
+                        axis(GamepadAxisType::LeftStickX),
+                        axis(GamepadAxisType::LeftStickY),
+                    );
+                }
+            }
+        }
+
+        // Limit the length so that diagonal movement has the same speed as
+        // horizontal and vertical movement, while keeping analog sticks analog.
+        controller.0 = intent.clamp_length_max(1.0);
     }
+}
 
-    // Normalize so that diagonal movement has the same speed as
-    // horizontal and vertical movement.
-    let intent = intent.normalize_or_zero();
+/// The direction an entity looks in, following its [`MovementController`].
+/// It keeps looking the same way while standing still.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub struct Facing(pub Dir2);
 
-    // Apply movement intent to controllers.
-    for mut controller in &mut controller_query {
-        controller.0 = intent;
+impl Default for Facing {
+    fn default() -> Self {
+        Self(Dir2::X)
     }
 }
 
-#[derive(Component, Reflect)]
-#[reflect(Component)]
+pub(crate) fn update_facing(mut facing_query: Query<(&MovementController, &mut Facing)>) {
+    for (controller, mut facing) in &mut facing_query {
+        if let Ok(direction) = Dir2::new(controller.0) {
+            if facing.0 != direction {
+                facing.0 = direction;
+            }
+        }
+    }
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
 pub struct Movement {
     /// Since Bevy's default 2D camera setup is scaled such that
     /// one unit is one pixel, you can think of this as
     /// "How many pixels per second should the player move?"
     /// Note that physics engines may use different unit/pixel ratios.
     pub speed: f32,
+    /// The current velocity in pixels per second.
+    /// It follows the [`MovementController`] quickly, unless the ground is slippery.
+    pub velocity: Vec2,
 }
 
-fn apply_movement(
-    time: Res<Time>,
-    mut movement_query: Query<(&MovementController, &Movement, &mut Transform)>,
-) {
-    for (controller, movement, mut transform) in &mut movement_query {
-        let velocity = movement.speed * controller.0;
-        transform.translation += velocity.extend(0.0) * time.delta_seconds();
+/// Changes how an entity moves, e.g. depending on the ground it stands on.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct MovementModifiers {
+    /// Multiplies [`Movement::speed`].
+    pub speed_multiplier: f32,
+    /// Multiplies how quickly the velocity follows the [`MovementController`].
+    /// Lower values make movement slippery.
+    pub traction: f32,
+    /// Added to the velocity without affecting it, e.g. by a conveyor belt.
+    pub push: Vec2,
+}
+
+impl Default for MovementModifiers {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: 1.0,
+            traction: 1.0,
+            push: Vec2::ZERO,
+        }
     }
 }
 
-#[derive(Component, Reflect)]
-#[reflect(Component)]
-pub struct WrapWithinWindow;
+/// How quickly the velocity follows the controller with full traction, in 1/s.
+const ACCELERATION: f32 = 20.0;
 
-fn wrap_within_window(
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    mut wrap_query: Query<&mut Transform, With<WrapWithinWindow>>,
+pub(crate) fn apply_movement(
+    time: Res<Time>,
+    mut movement_query: Query<(
+        &MovementController,
+        &mut Movement,
+        Option<&MovementModifiers>,
+        &mut Transform,
+    )>,
 ) {
-    let size = window_query.single().size() + 256.0;
-    let half_size = size / 2.0;
-    for mut transform in &mut wrap_query {
-        let position = transform.translation.xy();
-        let wrapped = (position + half_size).rem_euclid(size) - half_size;
-        transform.translation = wrapped.extend(transform.translation.z);
+    let dt = time.delta_seconds();
+    for (controller, mut movement, modifiers, mut transform) in &mut movement_query {
+        let modifiers = modifiers.copied().unwrap_or_default();
+        let target = movement.speed * modifiers.speed_multiplier * controller.0;
+        let smoothing = 1.0 - (-ACCELERATION * modifiers.traction * dt).exp();
+        movement.velocity = movement.velocity.lerp(target, smoothing);
+        let velocity = movement.velocity + modifiers.push;
+        transform.translation += velocity.extend(0.0) * dt;
     }
 }
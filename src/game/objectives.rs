@@ -0,0 +1,247 @@
+//! Per-level objectives and the win and loss conditions derived from them.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    chaser::{ChaserTrapped, PlayerCaught},
+    checkpoint::LastCheckpoint,
+    health::Died,
+    level::{FailReason, LevelCompleted, LevelDef, LevelFailed, PickupKind},
+    pickup::PickupCollected,
+    spawn::{exit::Exit, player::Player},
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Objectives>();
+    app.add_systems(
+        Update,
+        (
+            tick_objectives.in_set(AppSet::TickTimers),
+            (reach_exit, check_objectives)
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Playing).and_then(resource_exists::<Objectives>)),
+    );
+    app.observe(count_collected_pickups);
+    app.observe(count_trapped_chasers);
+    app.observe(fail_when_caught);
+    app.observe(fail_when_killed);
+}
+
+/// Something the player has to do to complete a level.
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum ObjectiveDef {
+    /// Stay alive for the given number of seconds.
+    Survive { secs: f32 },
+    /// Collect every pickup of the given kind.
+    CollectAll(PickupKind),
+    /// Walk onto the level's exit.
+    ReachExit,
+    /// Lure the boss into a trap.
+    DefeatBoss,
+    /// Lure the given number of chasers into traps.
+    TrapChasers { count: u32 },
+}
+
+/// Points awarded for each completed objective.
+const OBJECTIVE_SCORE: u32 = 100;
+/// Points awarded for each second the level is completed under its par time.
+const TIME_BONUS_PER_SEC: f32 = 5.0;
+
+/// Progress towards the objectives of the current level.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct Objectives {
+    pub objectives: Vec<Objective>,
+    /// Seconds since the level started.
+    pub elapsed_secs: f32,
+    pub time_limit_secs: Option<f32>,
+    par_time_secs: f32,
+    /// Whether the level was already completed or failed.
+    finished: bool,
+}
+
+#[derive(Reflect, Debug)]
+pub struct Objective {
+    pub def: ObjectiveDef,
+    pub progress: f32,
+    pub target: f32,
+}
+
+impl Objective {
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.target
+    }
+
+    /// A short description of the objective and its progress, for the HUD.
+    pub fn describe(&self) -> String {
+        let (progress, target) = (self.progress.floor() as u32, self.target as u32);
+        match &self.def {
+            ObjectiveDef::Survive { .. } => format!("Survive: {progress}/{target}s"),
+            ObjectiveDef::CollectAll(PickupKind::Egg) => {
+                format!("Collect eggs: {progress}/{target}")
+            }
+            ObjectiveDef::CollectAll(PickupKind::Key) => {
+                format!("Collect keys: {progress}/{target}")
+            }
+            ObjectiveDef::ReachExit => "Reach the exit".to_string(),
+            ObjectiveDef::DefeatBoss => "Lure the boss into a trap".to_string(),
+            ObjectiveDef::TrapChasers { .. } => {
+                format!("Lure chasers into traps: {progress}/{target}")
+            }
+        }
+    }
+}
+
+impl Objectives {
+    pub fn new(level: &LevelDef) -> Self {
+        let objectives = level
+            .objectives
+            .iter()
+            .map(|def| Objective {
+                def: def.clone(),
+                progress: 0.0,
+                target: match def {
+                    ObjectiveDef::Survive { secs } => *secs,
+                    ObjectiveDef::CollectAll(kind) => level
+                        .pickups
+                        .iter()
+                        .filter(|pickup| pickup.kind == *kind)
+                        .count() as f32,
+                    ObjectiveDef::TrapChasers { count } => *count as f32,
+                    ObjectiveDef::ReachExit | ObjectiveDef::DefeatBoss => 1.0,
+                },
+            })
+            .collect();
+        Self {
+            objectives,
+            elapsed_secs: 0.0,
+            time_limit_secs: level.time_limit_secs,
+            par_time_secs: level.par_time_secs,
+            finished: false,
+        }
+    }
+
+    /// Seconds left before the time limit runs out, if there is one.
+    pub fn remaining_secs(&self) -> Option<f32> {
+        self.time_limit_secs
+            .map(|limit| (limit - self.elapsed_secs).max(0.0))
+    }
+
+    pub fn score(&self) -> u32 {
+        let completed = self.objectives.iter().filter(|o| o.is_complete()).count() as u32;
+        let time_bonus = (self.par_time_secs - self.elapsed_secs).max(0.0) * TIME_BONUS_PER_SEC;
+        completed * OBJECTIVE_SCORE + time_bonus as u32
+    }
+
+    /// Mark the level as finished. Return `false` if it already was.
+    fn finish(&mut self) -> bool {
+        !std::mem::replace(&mut self.finished, true)
+    }
+
+    /// Add `amount` to the progress of every objective matching `predicate`.
+    fn advance(&mut self, amount: f32, predicate: impl Fn(&ObjectiveDef) -> bool) {
+        for objective in &mut self.objectives {
+            if predicate(&objective.def) {
+                objective.progress = (objective.progress + amount).min(objective.target);
+            }
+        }
+    }
+}
+
+fn tick_objectives(time: Res<Time>, mut objectives: ResMut<Objectives>) {
+    let delta = time.delta_seconds();
+    objectives.elapsed_secs += delta;
+    objectives.advance(delta, |def| matches!(def, ObjectiveDef::Survive { .. }));
+}
+
+/// How close the player has to get to the exit to reach it.
+const EXIT_REACH_DISTANCE: f32 = 48.0;
+
+fn reach_exit(
+    player_query: Query<&Transform, With<Player>>,
+    exit_query: Query<&Transform, With<Exit>>,
+    mut objectives: ResMut<Objectives>,
+) {
+    let reached = player_query.iter().any(|player| {
+        exit_query.iter().any(|exit| {
+            player.translation.xy().distance(exit.translation.xy()) < EXIT_REACH_DISTANCE
+        })
+    });
+    if reached {
+        objectives.advance(1.0, |def| *def == ObjectiveDef::ReachExit);
+    }
+}
+
+fn count_collected_pickups(
+    trigger: Trigger<PickupCollected>,
+    objectives: Option<ResMut<Objectives>>,
+) {
+    let Some(mut objectives) = objectives else {
+        return;
+    };
+    let kind = trigger.event().0;
+    objectives.advance(1.0, |def| *def == ObjectiveDef::CollectAll(kind));
+}
+
+fn count_trapped_chasers(trigger: Trigger<ChaserTrapped>, objectives: Option<ResMut<Objectives>>) {
+    let Some(mut objectives) = objectives else {
+        return;
+    };
+    objectives.advance(1.0, |def| matches!(def, ObjectiveDef::TrapChasers { .. }));
+    if trigger.event().boss {
+        objectives.advance(1.0, |def| *def == ObjectiveDef::DefeatBoss);
+    }
+}
+
+/// Fail the level when the player is caught, unless they can respawn at a checkpoint.
+fn fail_when_caught(
+    trigger: Trigger<PlayerCaught>,
+    mut commands: Commands,
+    objectives: Option<ResMut<Objectives>>,
+    player_query: Query<Has<LastCheckpoint>, With<Player>>,
+) {
+    if player_query.get(trigger.entity()) != Ok(false) {
+        return;
+    }
+    if objectives.is_some_and(|mut objectives| objectives.finish()) {
+        commands.trigger(LevelFailed {
+            reason: FailReason::Caught,
+        });
+    }
+}
+
+/// Fail the level when the player dies, unless they can respawn at a checkpoint.
+fn fail_when_killed(
+    trigger: Trigger<Died>,
+    mut commands: Commands,
+    objectives: Option<ResMut<Objectives>>,
+    player_query: Query<Has<LastCheckpoint>, With<Player>>,
+) {
+    if player_query.get(trigger.entity()) != Ok(false) {
+        return;
+    }
+    if objectives.is_some_and(|mut objectives| objectives.finish()) {
+        commands.trigger(LevelFailed {
+            reason: FailReason::Killed,
+        });
+    }
+}
+
+/// Complete the level once all objectives are done, or fail it when time runs out.
+fn check_objectives(mut commands: Commands, mut objectives: ResMut<Objectives>) {
+    if objectives.objectives.iter().all(Objective::is_complete) {
+        if objectives.finish() {
+            commands.trigger(LevelCompleted {
+                score: objectives.score(),
+            });
+        }
+    } else if objectives.remaining_secs() == Some(0.0) && objectives.finish() {
+        commands.trigger(LevelFailed {
+            reason: FailReason::TimeUp,
+        });
+    }
+}
@@ -0,0 +1,282 @@
+//! How a particle effect looks, loaded from `.particles.ron` files.
+
+use std::{error::Error, f32::consts::TAU, fmt, io};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::game::layers::DrawLayer;
+
+/// How particles are emitted, how they move and how they change over their lifetime.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct ParticleEffect {
+    /// Particles per second while an emitter is emitting.
+    pub rate: f32,
+    /// Particles per burst.
+    pub burst: u32,
+    /// The shortest and longest lifetime of a particle, in seconds.
+    pub lifetime_secs: (f32, f32),
+    /// The slowest and fastest initial speed of a particle.
+    pub speed: (f32, f32),
+    /// The middle of the cone particles are emitted in, in radians counter-clockwise
+    /// from the right.
+    pub direction: f32,
+    /// How wide the cone is, in radians.
+    pub spread: f32,
+    /// Acceleration of every particle.
+    pub gravity: Vec2,
+    /// Width and height of a particle.
+    pub size: f32,
+    /// Color over the particle's lifetime, by how much of it has passed from 0 to 1.
+    pub colors: Vec<(f32, Color)>,
+    /// Scale over the particle's lifetime, by how much of it has passed from 0 to 1.
+    pub scales: Vec<(f32, f32)>,
+    pub layer: DrawLayer,
+}
+
+impl ParticleEffect {
+    /// A random lifetime and initial velocity for a new particle.
+    pub fn roll(&self, rng: &mut impl Rng) -> (f32, Vec2) {
+        let lifetime = rng.gen_range(self.lifetime_secs.0..=self.lifetime_secs.1);
+        let speed = rng.gen_range(self.speed.0..=self.speed.1);
+        let angle = self.direction + rng.gen_range(-0.5..=0.5) * self.spread;
+        (lifetime, Vec2::from_angle(angle) * speed)
+    }
+
+    /// The color at `t`, from 0 at the start to 1 at the end of the particle's lifetime.
+    pub fn color(&self, t: f32) -> Color {
+        sample(&self.colors, t, |start, end, ratio| start.mix(&end, ratio))
+    }
+
+    /// The scale at `t`, from 0 at the start to 1 at the end of the particle's lifetime.
+    pub fn scale(&self, t: f32) -> f32 {
+        sample(&self.scales, t, |start, end, ratio| start.lerp(end, ratio))
+    }
+}
+
+/// Interpolate between the two keys around `t`. Keys are sorted and not empty.
+fn sample<T: Copy>(keys: &[(f32, T)], t: f32, lerp: impl Fn(T, T, f32) -> T) -> T {
+    let next = keys.partition_point(|&(key, _)| key <= t);
+    match (next.checked_sub(1).map(|index| keys[index]), keys.get(next)) {
+        (Some((from, start)), Some(&(to, end))) => lerp(start, end, (t - from) / (to - from)),
+        (Some((_, value)), None) | (None, Some(&(_, value))) => value,
+        (None, None) => unreachable!("curves have at least one key"),
+    }
+}
+
+/// The on-disk representation of a [`ParticleEffect`].
+#[derive(Deserialize)]
+struct ParticleEffectFile {
+    #[serde(default)]
+    rate: f32,
+    #[serde(default)]
+    burst: u32,
+    lifetime_secs: (f32, f32),
+    speed: (f32, f32),
+    #[serde(default)]
+    direction_degrees: f32,
+    /// Emit in every direction by default.
+    #[serde(default = "full_circle")]
+    spread_degrees: f32,
+    #[serde(default)]
+    gravity: Vec2,
+    size: f32,
+    /// Colors as hex codes, e.g. `"#ffffff80"`.
+    colors: Vec<(f32, String)>,
+    #[serde(default = "constant_scale")]
+    scales: Vec<(f32, f32)>,
+    #[serde(default = "effects_layer")]
+    layer: DrawLayer,
+}
+
+fn full_circle() -> f32 {
+    360.0
+}
+
+fn constant_scale() -> Vec<(f32, f32)> {
+    vec![(0.0, 1.0)]
+}
+
+fn effects_layer() -> DrawLayer {
+    DrawLayer::Effects
+}
+
+impl ParticleEffectFile {
+    fn into_effect(self) -> Result<ParticleEffect, ParticleEffectError> {
+        let ranges = [("lifetime_secs", self.lifetime_secs), ("speed", self.speed)];
+        if let Some((field, _)) = ranges
+            .into_iter()
+            .find(|(_, (min, max))| !(0.0..=*max).contains(min))
+        {
+            return Err(ParticleEffectError::InvalidRange(field));
+        }
+        if !is_curve(&self.colors) {
+            return Err(ParticleEffectError::InvalidCurve("colors"));
+        }
+        if !is_curve(&self.scales) {
+            return Err(ParticleEffectError::InvalidCurve("scales"));
+        }
+        let colors = self
+            .colors
+            .into_iter()
+            .map(|(t, hex)| match Srgba::hex(&hex) {
+                Ok(color) => Ok((t, color.into())),
+                Err(_) => Err(ParticleEffectError::InvalidColor(hex)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ParticleEffect {
+            rate: self.rate.max(0.0),
+            burst: self.burst,
+            lifetime_secs: self.lifetime_secs,
+            speed: self.speed,
+            direction: self.direction_degrees.to_radians(),
+            spread: self.spread_degrees.to_radians().clamp(0.0, TAU),
+            gravity: self.gravity,
+            size: self.size,
+            colors,
+            scales: self.scales,
+            layer: self.layer,
+        })
+    }
+}
+
+/// Whether `keys` is not empty and sorted.
+fn is_curve<T>(keys: &[(f32, T)]) -> bool {
+    !keys.is_empty() && keys.windows(2).all(|pair| pair[0].0 < pair[1].0)
+}
+
+#[derive(Default)]
+pub struct ParticleEffectLoader;
+
+impl AssetLoader for ParticleEffectLoader {
+    type Asset = ParticleEffect;
+    type Settings = ();
+    type Error = ParticleEffectError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        ron::de::from_bytes::<ParticleEffectFile>(&bytes)?.into_effect()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["particles.ron"]
+    }
+}
+
+#[derive(Debug)]
+pub enum ParticleEffectError {
+    Io(io::Error),
+    Ron(ron::error::SpannedError),
+    /// A range is negative or its minimum is above its maximum.
+    InvalidRange(&'static str),
+    /// A curve has no keys or its keys are out of order.
+    InvalidCurve(&'static str),
+    /// A color is not a valid hex code.
+    InvalidColor(String),
+}
+
+impl fmt::Display for ParticleEffectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read file: {error}"),
+            Self::Ron(error) => write!(f, "could not parse file: {error}"),
+            Self::InvalidRange(field) => {
+                write!(
+                    f,
+                    "{field} must be between 0 and a maximum above its minimum"
+                )
+            }
+            Self::InvalidCurve(field) => {
+                write!(f, "{field} must have at least one key, in increasing order")
+            }
+            Self::InvalidColor(hex) => write!(f, "{hex:?} is not a hex color"),
+        }
+    }
+}
+
+impl Error for ParticleEffectError {}
+
+impl From<io::Error> for ParticleEffectError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for ParticleEffectError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<ParticleEffect, ParticleEffectError> {
+        ron::de::from_str::<ParticleEffectFile>(text)?.into_effect()
+    }
+
+    #[test]
+    fn shipped_effects_are_valid() {
+        for text in [
+            include_str!("../../../assets/particles/dust.particles.ron"),
+            include_str!("../../../assets/particles/feathers.particles.ron"),
+            include_str!("../../../assets/particles/sparkles.particles.ron"),
+        ] {
+            parse(text).unwrap();
+        }
+    }
+
+    #[test]
+    fn curves_are_interpolated() {
+        let effect = parse(
+            r##"(
+                lifetime_secs: (1.0, 1.0),
+                speed: (0.0, 10.0),
+                size: 4.0,
+                colors: [(0.0, "#000000"), (1.0, "#ffffff")],
+                scales: [(0.0, 1.0), (0.5, 2.0)],
+            )"##,
+        )
+        .unwrap();
+        assert_eq!(effect.scale(0.25), 1.5);
+        assert_eq!(effect.scale(1.0), 2.0);
+        assert_eq!(effect.color(0.0), Color::from(Srgba::BLACK));
+        assert_eq!(effect.spread, TAU);
+    }
+
+    #[test]
+    fn invalid_effects_are_rejected() {
+        let effect = |lifetime: &str, colors: &str| {
+            parse(&format!(
+                "(lifetime_secs: {lifetime}, speed: (0.0, 1.0), size: 1.0, colors: {colors})"
+            ))
+        };
+        assert!(matches!(
+            effect("(2.0, 1.0)", r##"[(0.0, "#ffffff")]"##),
+            Err(ParticleEffectError::InvalidRange("lifetime_secs"))
+        ));
+        assert!(matches!(
+            effect("(1.0, 2.0)", "[]"),
+            Err(ParticleEffectError::InvalidCurve("colors"))
+        ));
+        assert!(matches!(
+            effect("(1.0, 2.0)", r##"[(1.0, "#ffffff"), (0.0, "#000000")]"##),
+            Err(ParticleEffectError::InvalidCurve("colors"))
+        ));
+        assert!(matches!(
+            effect("(1.0, 2.0)", r#"[(0.0, "white")]"#),
+            Err(ParticleEffectError::InvalidColor(_))
+        ));
+    }
+}
@@ -0,0 +1,285 @@
+//! Pooled sprite particles, e.g. dust puffs, feathers and sparkles.
+//! A [`ParticleEmitter`] emits particles of a [`ParticleEffect`] continuously, or in bursts
+//! when [`EmitParticles`] is triggered on it. [`SpawnParticles`] spawns a burst anywhere.
+//! At most [`MAX_PARTICLES`] exist at once, finished particles are hidden and reused.
+
+pub mod effect;
+
+use bevy::prelude::*;
+
+use self::effect::{ParticleEffect, ParticleEffectLoader};
+use super::{
+    animation::{AnimationEvent, FOOTSTEP_TAG},
+    assets::{HandleMap, ParticleKey},
+    health::{Damage, Invulnerable},
+    spawn::player::Player,
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<ParticleEffect>();
+    app.init_asset_loader::<ParticleEffectLoader>();
+    app.register_type::<(ParticleEmitter, Particle)>();
+    app.init_resource::<ParticlePool>();
+    app.add_systems(
+        Update,
+        (emit_particles, update_particles)
+            .chain()
+            .in_set(AppSet::Update),
+    );
+    app.add_systems(OnExit(Screen::Playing), clear_pool);
+    app.observe(emit_burst);
+    app.observe(spawn_particles);
+
+    // Effects for things happening in the game.
+    app.observe(puff_dust_on_footsteps);
+    app.observe(scatter_feathers_on_hit);
+}
+
+/// Emits particles of an effect from the entity's position.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct ParticleEmitter {
+    pub effect: Handle<ParticleEffect>,
+    /// Emit the effect's rate of particles per second, otherwise only emit bursts.
+    pub emitting: bool,
+    /// Particles owed since the last frame, so that rates below the frame rate work.
+    pending: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: Handle<ParticleEffect>) -> Self {
+        Self {
+            effect,
+            emitting: true,
+            pending: 0.0,
+        }
+    }
+}
+
+/// Trigger this event on an entity with a [`ParticleEmitter`] to emit a burst of its effect.
+#[derive(Event, Debug)]
+pub struct EmitParticles;
+
+/// Trigger this event to emit a burst of `effect` at `translation`.
+#[derive(Event, Debug)]
+pub struct SpawnParticles {
+    pub effect: Handle<ParticleEffect>,
+    pub translation: Vec2,
+}
+
+/// A particle that is alive. Pooled particles without it are hidden.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Particle {
+    pub effect: Handle<ParticleEffect>,
+    pub velocity: Vec2,
+    /// Seconds since the particle was emitted.
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+/// How many particles can exist at once. Particles emitted beyond this are dropped.
+pub const MAX_PARTICLES: usize = 512;
+
+/// Hidden particles waiting to be reused.
+#[derive(Resource, Debug, Default)]
+struct ParticlePool {
+    free: Vec<Entity>,
+    /// How many particles exist, hidden or not.
+    count: usize,
+}
+
+impl ParticlePool {
+    /// Emit `amount` particles of `effect` at `translation`, as far as the cap allows.
+    fn emit(
+        &mut self,
+        commands: &mut Commands,
+        handle: &Handle<ParticleEffect>,
+        effect: &ParticleEffect,
+        translation: Vec2,
+        amount: u32,
+    ) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..amount {
+            let (lifetime, velocity) = effect.roll(&mut rng);
+            let particle = (
+                Particle {
+                    effect: handle.clone(),
+                    velocity,
+                    age: 0.0,
+                    lifetime,
+                },
+                Sprite {
+                    color: effect.color(0.0),
+                    custom_size: Some(Vec2::splat(effect.size)),
+                    ..default()
+                },
+                Transform::from_translation(translation.extend(0.0))
+                    .with_scale(Vec3::splat(effect.scale(0.0))),
+                Visibility::Inherited,
+                effect.layer,
+            );
+            if let Some(entity) = self.free.pop() {
+                commands.entity(entity).insert(particle);
+            } else if self.count < MAX_PARTICLES {
+                self.count += 1;
+                commands.spawn((
+                    Name::new("Particle"),
+                    SpriteBundle::default(),
+                    particle,
+                    StateScoped(Screen::Playing),
+                ));
+            } else {
+                return;
+            }
+        }
+    }
+}
+
+fn emit_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    effects: Res<Assets<ParticleEffect>>,
+    mut pool: ResMut<ParticlePool>,
+    mut emitter_query: Query<(&GlobalTransform, &mut ParticleEmitter)>,
+) {
+    for (transform, mut emitter) in &mut emitter_query {
+        if !emitter.emitting {
+            continue;
+        }
+        let Some(effect) = effects.get(&emitter.effect) else {
+            continue;
+        };
+        emitter.pending += effect.rate * time.delta_seconds();
+        let amount = emitter.pending.floor();
+        if amount < 1.0 {
+            continue;
+        }
+        emitter.pending -= amount;
+        let translation = transform.translation().xy();
+        pool.emit(
+            &mut commands,
+            &emitter.effect,
+            effect,
+            translation,
+            amount as u32,
+        );
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    effects: Res<Assets<ParticleEffect>>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut Sprite,
+        &mut Visibility,
+    )>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut particle, mut transform, mut sprite, mut visibility) in &mut particle_query {
+        particle.age += delta;
+        let Some(effect) = effects
+            .get(&particle.effect)
+            .filter(|_| particle.age < particle.lifetime)
+        else {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).remove::<Particle>();
+            pool.free.push(entity);
+            continue;
+        };
+        particle.velocity += effect.gravity * delta;
+        transform.translation += (particle.velocity * delta).extend(0.0);
+        let t = particle.age / particle.lifetime;
+        transform.scale = Vec3::splat(effect.scale(t));
+        sprite.color = effect.color(t);
+    }
+}
+
+/// Particles are despawned with the rest of the level.
+fn clear_pool(mut pool: ResMut<ParticlePool>) {
+    *pool = ParticlePool::default();
+}
+
+fn emit_burst(
+    trigger: Trigger<EmitParticles>,
+    mut commands: Commands,
+    effects: Res<Assets<ParticleEffect>>,
+    mut pool: ResMut<ParticlePool>,
+    emitter_query: Query<(&GlobalTransform, &ParticleEmitter)>,
+) {
+    let Ok((transform, emitter)) = emitter_query.get(trigger.entity()) else {
+        return;
+    };
+    let Some(effect) = effects.get(&emitter.effect) else {
+        return;
+    };
+    let translation = transform.translation().xy();
+    pool.emit(
+        &mut commands,
+        &emitter.effect,
+        effect,
+        translation,
+        effect.burst,
+    );
+}
+
+fn spawn_particles(
+    trigger: Trigger<SpawnParticles>,
+    mut commands: Commands,
+    effects: Res<Assets<ParticleEffect>>,
+    mut pool: ResMut<ParticlePool>,
+) {
+    let event = trigger.event();
+    let Some(effect) = effects.get(&event.effect) else {
+        return;
+    };
+    pool.emit(
+        &mut commands,
+        &event.effect,
+        effect,
+        event.translation,
+        effect.burst,
+    );
+}
+
+/// Where the ducky's feet are, relative to the player's position.
+const FEET_OFFSET: Vec2 = Vec2::new(0.0, -40.0);
+
+fn puff_dust_on_footsteps(
+    trigger: Trigger<AnimationEvent>,
+    mut commands: Commands,
+    particle_handles: Res<HandleMap<ParticleKey>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    if trigger.event().tag != FOOTSTEP_TAG {
+        return;
+    }
+    let Ok(transform) = player_query.get(trigger.entity()) else {
+        return;
+    };
+    commands.trigger(SpawnParticles {
+        effect: particle_handles[&ParticleKey::Dust].clone(),
+        translation: transform.translation.xy() + FEET_OFFSET,
+    });
+}
+
+fn scatter_feathers_on_hit(
+    trigger: Trigger<Damage>,
+    mut commands: Commands,
+    particle_handles: Res<HandleMap<ParticleKey>>,
+    player_query: Query<&Transform, (With<Player>, Without<Invulnerable>)>,
+) {
+    let Ok(transform) = player_query.get(trigger.entity()) else {
+        return;
+    };
+    commands.trigger(SpawnParticles {
+        effect: particle_handles[&ParticleKey::Feathers].clone(),
+        translation: transform.translation.xy(),
+    });
+}
@@ -0,0 +1,43 @@
+//! Save data that is kept between sessions, like [`Progress`](super::progress::Progress)
+//! and [`Settings`](super::settings::Settings).
+//! Native builds save it as RON files in the working directory.
+//! Web builds only keep it for the current session.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Load a value saved to `file_name`, or the default value if there is none.
+#[cfg(not(target_family = "wasm"))]
+pub(super) fn load<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    use bevy::{asset::ron, log::warn};
+
+    let Ok(contents) = std::fs::read_to_string(file_name) else {
+        return T::default();
+    };
+    ron::de::from_str(&contents).unwrap_or_else(|error| {
+        warn!("Ignoring invalid save file {file_name}: {error}");
+        T::default()
+    })
+}
+
+#[cfg(target_family = "wasm")]
+pub(super) fn load<T: DeserializeOwned + Default>(_file_name: &str) -> T {
+    T::default()
+}
+
+/// Save `value` to `file_name`, logging an error if that fails.
+#[cfg(not(target_family = "wasm"))]
+pub(super) fn save<T: Serialize>(value: &T, file_name: &str) {
+    use bevy::{asset::ron, log::error, utils::default};
+
+    let result = ron::ser::to_string_pretty(value, default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| {
+            std::fs::write(file_name, contents).map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        error!("Could not save {file_name}: {error}");
+    }
+}
+
+#[cfg(target_family = "wasm")]
+pub(super) fn save<T: Serialize>(_value: &T, _file_name: &str) {}
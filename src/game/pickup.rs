@@ -0,0 +1,44 @@
+//! Let the player collect pickups by walking over them.
+
+use bevy::prelude::*;
+
+use super::{
+    juice::PunchScale,
+    level::PickupKind,
+    particles::EmitParticles,
+    spawn::{pickup::Pickup, player::Player},
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, collect_pickups.in_set(AppSet::Update));
+}
+
+/// Triggered on the player who collects a pickup.
+#[derive(Event, Debug)]
+pub struct PickupCollected(pub PickupKind);
+
+/// How close the player has to get to a pickup to collect it.
+const COLLECT_DISTANCE: f32 = 64.0;
+
+/// How much the player grows for a moment when they collect a pickup.
+const COLLECT_PUNCH: f32 = 0.25;
+
+fn collect_pickups(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    pickup_query: Query<(Entity, &Transform, &Pickup)>,
+) {
+    for (entity, transform, pickup) in &pickup_query {
+        let collector = player_query.iter().find(|(_, player)| {
+            player.translation.xy().distance(transform.translation.xy()) < COLLECT_DISTANCE
+        });
+        if let Some((player, _)) = collector {
+            commands.trigger_targets(PunchScale::uniform(COLLECT_PUNCH), player);
+            // Burst into sparkles before disappearing.
+            commands.trigger_targets(EmitParticles, entity);
+            commands.entity(entity).despawn_recursive();
+            commands.trigger_targets(PickupCollected(pickup.0), player);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! The player's campaign progress, saved between sessions to [`SAVE_PATH`].
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    level::{CurrentLevel, LevelCompleted, LevelId},
+    persist,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Progress>();
+    app.insert_resource(persist::load::<Progress>(SAVE_PATH));
+    app.observe(record_completed_level);
+}
+
+#[derive(Resource, Reflect, Debug, Default, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct Progress {
+    /// Completed levels and their best score.
+    best_scores: HashMap<LevelId, u32>,
+}
+
+const SAVE_PATH: &str = "progress.ron";
+
+impl Progress {
+    pub fn is_completed(&self, id: &LevelId) -> bool {
+        self.best_scores.contains_key(id)
+    }
+
+    /// Return the best score of a completed level.
+    pub fn best_score(&self, id: &LevelId) -> Option<u32> {
+        self.best_scores.get(id).copied()
+    }
+
+    /// Record that a level was completed with `score`.
+    fn record(&mut self, id: LevelId, score: u32) {
+        let best_score = self.best_scores.entry(id).or_default();
+        *best_score = score.max(*best_score);
+    }
+}
+
+/// Completing a level records its score, which also unlocks the next level.
+/// Play tests of the level editor aren't recorded.
+fn record_completed_level(
+    trigger: Trigger<LevelCompleted>,
+    current_level: Res<CurrentLevel>,
+    mut progress: ResMut<Progress>,
+) {
+    if current_level.0.is_playtest() {
+        return;
+    }
+    progress.record(current_level.0.clone(), trigger.event().score);
+    persist::save(&*progress, SAVE_PATH);
+}
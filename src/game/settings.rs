@@ -0,0 +1,72 @@
+//! Player settings, saved between sessions to [`SAVE_PATH`].
+
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::persist;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Settings>();
+    app.insert_resource(persist::load::<Settings>(SAVE_PATH));
+    app.add_systems(Update, save_settings.run_if(resource_changed::<Settings>));
+}
+
+#[derive(Resource, Reflect, Debug, Default, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    /// How strongly the camera shakes, see [`CameraShake`](super::camera::shake::CameraShake).
+    pub screen_shake: EffectLevel,
+    /// How long the game freezes on big hits, see [`HitStop`](super::hit_stop::HitStop).
+    pub hit_stop: EffectLevel,
+}
+
+/// Accessibility setting for effects that some players find uncomfortable.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum EffectLevel {
+    #[default]
+    Full,
+    Reduced,
+    Off,
+}
+
+impl EffectLevel {
+    /// How much of the effect to apply.
+    pub fn factor(self) -> f32 {
+        match self {
+            Self::Full => 1.0,
+            Self::Reduced => 0.4,
+            Self::Off => 0.0,
+        }
+    }
+
+    /// The next level to switch to in the settings screen.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Full => Self::Reduced,
+            Self::Reduced => Self::Off,
+            Self::Off => Self::Full,
+        }
+    }
+}
+
+impl fmt::Display for EffectLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "Full"),
+            Self::Reduced => write!(f, "Reduced"),
+            Self::Off => write!(f, "Off"),
+        }
+    }
+}
+
+const SAVE_PATH: &str = "settings.ron";
+
+fn save_settings(settings: Res<Settings>) {
+    // Inserting the resource on startup counts as a change, but there is nothing new to save.
+    if !settings.is_added() {
+        persist::save(&*settings, SAVE_PATH);
+    }
+}
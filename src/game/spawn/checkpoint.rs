@@ -0,0 +1,51 @@
+//! Spawn checkpoints the player respawns at after being caught.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        layers::DrawLayer,
+        level::components::{ApplyComponentDefs, ComponentDef},
+    },
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_checkpoint);
+    app.register_type::<Checkpoint>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnCheckpoint {
+    pub translation: Vec2,
+    pub size: Vec2,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Checkpoint;
+
+const CHECKPOINT_COLOR: Color = Color::srgb(0.361, 0.631, 0.780);
+
+fn spawn_checkpoint(trigger: Trigger<SpawnCheckpoint>, mut commands: Commands) {
+    let event = trigger.event();
+    commands
+        .spawn((
+            Name::new("Checkpoint"),
+            Checkpoint,
+            SpriteBundle {
+                sprite: Sprite {
+                    color: CHECKPOINT_COLOR,
+                    custom_size: Some(event.size),
+                    ..default()
+                },
+                transform: Transform::from_translation(event.translation.extend(0.0)),
+                ..default()
+            },
+            DrawLayer::Decals,
+            StateScoped(Screen::Playing),
+        ))
+        .add(ApplyComponentDefs(event.components.clone()));
+}
@@ -0,0 +1,60 @@
+//! Spawn a door. The door's tile is drawn by the tilemap, see [`crate::game::door`].
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        level::components::{ApplyComponentDefs, ComponentDef},
+        tilemap::TileId,
+    },
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_door);
+    app.register_type::<Door>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnDoor {
+    pub name: String,
+    pub tile: UVec2,
+    /// The tile shown while the door is open.
+    pub open_tile: TileId,
+    pub translation: Vec2,
+    pub locked: bool,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Door {
+    pub name: String,
+    /// The tile blocked by the door while it is closed.
+    pub tile: UVec2,
+    /// The tile shown while the door is open, i.e. the level's tile under the door.
+    pub open_tile: TileId,
+    pub locked: bool,
+    pub open: bool,
+}
+
+fn spawn_door(trigger: Trigger<SpawnDoor>, mut commands: Commands) {
+    let event = trigger.event();
+    commands
+        .spawn((
+            Name::new("Door"),
+            Door {
+                name: event.name.clone(),
+                tile: event.tile,
+                open_tile: event.open_tile,
+                locked: event.locked,
+                open: false,
+            },
+            SpatialBundle::from_transform(Transform::from_translation(
+                event.translation.extend(0.0),
+            )),
+            StateScoped(Screen::Playing),
+        ))
+        .add(ApplyComponentDefs(event.components.clone()));
+}
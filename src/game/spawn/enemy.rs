@@ -0,0 +1,109 @@
+//! Spawn enemy spawners and the chasers they release.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        health::Health,
+        layers::YSort,
+        level::components::{ApplyComponentDefs, ComponentDef},
+        minimap::MapIcon,
+        movement::{Facing, Movement, MovementController},
+        tilemap::collision::TileCollider,
+    },
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_enemy_spawner);
+    app.observe(spawn_chaser);
+    app.register_type::<(EnemySpawner, Chaser, Boss)>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnEnemySpawner {
+    pub translation: Vec2,
+    pub interval_secs: f32,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
+
+/// A location that periodically releases chasers into the level.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct EnemySpawner {
+    pub timer: Timer,
+}
+
+fn spawn_enemy_spawner(trigger: Trigger<SpawnEnemySpawner>, mut commands: Commands) {
+    let event = trigger.event();
+    commands
+        .spawn((
+            Name::new("Enemy Spawner"),
+            EnemySpawner {
+                timer: Timer::from_seconds(event.interval_secs, TimerMode::Repeating),
+            },
+            SpatialBundle::from_transform(Transform::from_translation(
+                event.translation.extend(0.0),
+            )),
+            StateScoped(Screen::Playing),
+        ))
+        .add(ApplyComponentDefs(event.components.clone()));
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnChaser {
+    pub translation: Vec2,
+    /// Whether to spawn the level's boss instead of a regular chaser.
+    pub boss: bool,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Chaser;
+
+/// Marks the chaser that has to be trapped for [`ObjectiveDef::DefeatBoss`](crate::game::objectives::ObjectiveDef::DefeatBoss).
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Boss;
+
+const CHASER_COLOR: Color = Color::srgb(0.851, 0.341, 0.388);
+const BOSS_COLOR: Color = Color::srgb(0.561, 0.145, 0.192);
+
+fn spawn_chaser(trigger: Trigger<SpawnChaser>, mut commands: Commands) {
+    let event = trigger.event();
+    let (name, color, size, speed, health) = if event.boss {
+        ("Boss", BOSS_COLOR, 96.0, 180.0, 5)
+    } else {
+        ("Chaser", CHASER_COLOR, 48.0, 250.0, 2)
+    };
+    let mut chaser = commands.spawn((
+        Name::new(name),
+        Chaser,
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            transform: Transform::from_translation(event.translation.extend(0.0)),
+            ..default()
+        },
+        Health::new(health),
+        MapIcon::new(color).with_size(size / 8.0).threat(),
+        MovementController::default(),
+        Movement { speed, ..default() },
+        Facing::default(),
+        TileCollider {
+            half_size: Vec2::splat(size / 2.0),
+        },
+        YSort::new(-size / 2.0),
+        StateScoped(Screen::Playing),
+    ));
+    if event.boss {
+        chaser.insert(Boss);
+    }
+    chaser.add(ApplyComponentDefs(event.components.clone()));
+}
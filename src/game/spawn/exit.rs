@@ -0,0 +1,53 @@
+//! Spawn the level exit.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        layers::DrawLayer,
+        level::components::{ApplyComponentDefs, ComponentDef},
+        minimap::MapIcon,
+    },
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_exit);
+    app.register_type::<Exit>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnExit {
+    pub translation: Vec2,
+    pub size: Vec2,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Exit;
+
+const EXIT_COLOR: Color = Color::srgb(0.361, 0.780, 0.431);
+
+fn spawn_exit(trigger: Trigger<SpawnExit>, mut commands: Commands) {
+    let event = trigger.event();
+    commands
+        .spawn((
+            Name::new("Exit"),
+            Exit,
+            MapIcon::new(EXIT_COLOR).with_size(8.0),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: EXIT_COLOR,
+                    custom_size: Some(event.size),
+                    ..default()
+                },
+                transform: Transform::from_translation(event.translation.extend(0.0)),
+                ..default()
+            },
+            DrawLayer::Decals,
+            StateScoped(Screen::Playing),
+        ))
+        .add(ApplyComponentDefs(event.components.clone()));
+}
@@ -1,18 +1,125 @@
-//! Spawn the main level by triggering other observers.
+//! Spawn a level from its [`LevelDef`] by triggering other observers.
 
 use bevy::prelude::*;
 
-use super::player::SpawnPlayer;
+use super::{
+    checkpoint::SpawnCheckpoint,
+    door::SpawnDoor,
+    enemy::{SpawnChaser, SpawnEnemySpawner},
+    exit::SpawnExit,
+    pickup::SpawnPickup,
+    player::{LocalPlayers, SpawnPlayer},
+    tilemap::SpawnTilemap,
+    trigger::SpawnTrigger,
+};
+use crate::game::{
+    audio::soundtrack::PlaySoundtrack,
+    level::{LevelDef, LevelId, Levels},
+    objectives::Objectives,
+    triggers::Signals,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_level);
 }
 
+/// Trigger this event to build the level with the given ID.
 #[derive(Event, Debug)]
-pub struct SpawnLevel;
+pub struct SpawnLevel(pub LevelId);
 
-fn spawn_level(_trigger: Trigger<SpawnLevel>, mut commands: Commands) {
-    // The only thing we have in our level is a player,
-    // but add things like walls etc. here.
-    commands.trigger(SpawnPlayer);
+fn spawn_level(
+    trigger: Trigger<SpawnLevel>,
+    mut commands: Commands,
+    levels: Res<Levels>,
+    level_defs: Res<Assets<LevelDef>>,
+    local_players: Res<LocalPlayers>,
+) {
+    let id = &trigger.event().0;
+    let Some(level) = levels.get(id).and_then(|handle| level_defs.get(handle)) else {
+        error!("Tried to spawn level {:?}, which is not loaded", id.0);
+        return;
+    };
+
+    commands.trigger(SpawnTilemap {
+        tilemap: level.to_tilemap(),
+        translation: level.tilemap_origin(),
+    });
+
+    for spawner in &level.enemy_spawners {
+        commands.trigger(SpawnEnemySpawner {
+            translation: level.tile_to_world(spawner.position),
+            interval_secs: spawner.interval_secs,
+            components: spawner.components.clone(),
+        });
+    }
+
+    for pickup in &level.pickups {
+        commands.trigger(SpawnPickup {
+            kind: pickup.kind,
+            translation: level.tile_to_world(pickup.position),
+            components: pickup.components.clone(),
+        });
+    }
+
+    for checkpoint in &level.checkpoints {
+        commands.trigger(SpawnCheckpoint {
+            translation: level.tile_to_world(checkpoint.position),
+            size: Vec2::splat(level.tile_size / 2.0),
+            components: checkpoint.components.clone(),
+        });
+    }
+
+    for door in &level.doors {
+        commands.trigger(SpawnDoor {
+            name: door.name.clone(),
+            tile: door.position,
+            open_tile: level.tile(door.position),
+            translation: level.tile_to_world(door.position),
+            locked: door.locked,
+            components: door.components.clone(),
+        });
+    }
+
+    for trigger in &level.triggers {
+        // The area's center lies between its top left and bottom right tiles.
+        let last_tile = trigger.position + trigger.size.saturating_sub(UVec2::ONE);
+        commands.trigger(SpawnTrigger {
+            name: trigger.name.clone(),
+            kind: trigger.kind,
+            translation: (level.tile_to_world(trigger.position) + level.tile_to_world(last_tile))
+                / 2.0,
+            size: trigger.size.as_vec2() * level.tile_size,
+            components: trigger.components.clone(),
+        });
+    }
+
+    if let Some(exit) = level.exit {
+        commands.trigger(SpawnExit {
+            translation: level.tile_to_world(exit),
+            size: Vec2::splat(level.tile_size),
+            components: level.components.exit.clone(),
+        });
+    }
+
+    if let Some(boss) = level.boss {
+        commands.trigger(SpawnChaser {
+            translation: level.tile_to_world(boss),
+            boss: true,
+            components: level.components.boss.clone(),
+        });
+    }
+
+    // Line up co-op players side by side within the spawn tile.
+    let count = local_players.count.max(1);
+    for index in 0..count {
+        let offset = (index as f32 - (count - 1) as f32 / 2.0) * level.tile_size / count as f32;
+        commands.trigger(SpawnPlayer {
+            index,
+            translation: level.tile_to_world(level.player_spawn) + Vec2::new(offset, 0.0),
+            components: level.components.player_spawn.clone(),
+        });
+    }
+    commands.trigger(PlaySoundtrack::Key(level.soundtrack));
+    commands.insert_resource(Objectives::new(level));
+    commands.insert_resource(Signals::new(level.wires.clone()));
 }
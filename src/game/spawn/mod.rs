@@ -4,9 +4,26 @@
 
 use bevy::prelude::*;
 
+pub mod checkpoint;
+pub mod door;
+pub mod enemy;
+pub mod exit;
 pub mod level;
+pub mod pickup;
 pub mod player;
+pub mod tilemap;
+pub mod trigger;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((level::plugin, player::plugin));
+    app.add_plugins((
+        checkpoint::plugin,
+        door::plugin,
+        enemy::plugin,
+        exit::plugin,
+        level::plugin,
+        pickup::plugin,
+        player::plugin,
+        tilemap::plugin,
+        trigger::plugin,
+    ));
 }
@@ -0,0 +1,73 @@
+//! Spawn a pickup.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        assets::{HandleMap, ParticleKey},
+        layers::YSort,
+        level::{
+            components::{ApplyComponentDefs, ComponentDef},
+            PickupKind,
+        },
+        minimap::MapIcon,
+        particles::ParticleEmitter,
+    },
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_pickup);
+    app.register_type::<Pickup>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnPickup {
+    pub kind: PickupKind,
+    pub translation: Vec2,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Pickup(pub PickupKind);
+
+fn spawn_pickup(
+    trigger: Trigger<SpawnPickup>,
+    mut commands: Commands,
+    particle_handles: Res<HandleMap<ParticleKey>>,
+) {
+    let event = trigger.event();
+    let (name, color, size) = match event.kind {
+        PickupKind::Egg => (
+            "Egg",
+            Color::srgb(0.957, 0.918, 0.780),
+            Vec2::new(24.0, 32.0),
+        ),
+        PickupKind::Key => (
+            "Key",
+            Color::srgb(0.878, 0.745, 0.275),
+            Vec2::new(32.0, 16.0),
+        ),
+    };
+    commands
+        .spawn((
+            Name::new(name),
+            Pickup(event.kind),
+            MapIcon::new(color),
+            ParticleEmitter::new(particle_handles[&ParticleKey::Sparkles].clone()),
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(event.translation.extend(0.0)),
+                ..default()
+            },
+            YSort::new(-size.y / 2.0),
+            StateScoped(Screen::Playing),
+        ))
+        .add(ApplyComponentDefs(event.components.clone()));
+}
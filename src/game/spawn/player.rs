@@ -4,55 +4,176 @@ use bevy::prelude::*;
 
 use crate::{
     game::{
-        animation::PlayerAnimation,
-        assets::{HandleMap, ImageKey},
-        movement::{Movement, MovementController, WrapWithinWindow},
+        animation::{
+            aseprite::SpriteSheet, direction::DirectionalAnimation, player_state_machine,
+            SpriteAnimation,
+        },
+        assets::{HandleMap, SpriteSheetKey},
+        camera::CameraTarget,
+        health::Health,
+        juice::{Juice, ScaleSpring, SquashStretch, WalkBob},
+        layers::YSort,
+        level::components::{ApplyComponentDefs, ComponentDef},
+        minimap::MapIcon,
+        movement::{Facing, Movement, MovementController},
+        tilemap::collision::TileCollider,
     },
     screen::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_player);
-    app.register_type::<Player>();
+    app.register_type::<(Player, PlayerControls, SpawnPoint, LocalPlayers)>();
+    app.init_resource::<LocalPlayers>();
 }
 
 #[derive(Event, Debug)]
-pub struct SpawnPlayer;
+pub struct SpawnPlayer {
+    /// Which of the [`LocalPlayers`] to spawn, starting at 0.
+    pub index: usize,
+    pub translation: Vec2,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
-pub struct Player;
+pub struct Player {
+    /// Which of the [`LocalPlayers`] this is, starting at 0.
+    pub index: usize,
+}
+
+/// How many players play on this machine, each with their own [`PlayerControls`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct LocalPlayers {
+    pub count: usize,
+}
+
+impl Default for LocalPlayers {
+    fn default() -> Self {
+        Self { count: 1 }
+    }
+}
+
+impl LocalPlayers {
+    pub const MAX: usize = 4;
+
+    /// How many players have controls: two on the keyboard and one per connected gamepad.
+    pub fn max_controllable(gamepads: &Gamepads) -> usize {
+        (2 + gamepads.iter().count()).min(Self::MAX)
+    }
+}
+
+/// The input a player is controlled with.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum PlayerControls {
+    /// Both WASD and the arrow keys, for playing alone.
+    Keyboard,
+    Wasd,
+    Arrows,
+    /// The nth connected gamepad, ordered by [`Gamepad::id`].
+    Gamepad(usize),
+}
+
+impl PlayerControls {
+    /// Hand out controls so that the first two players share the keyboard
+    /// and everyone else uses a gamepad.
+    pub fn for_player(index: usize, count: usize) -> Self {
+        match (index, count) {
+            (0, 1) => Self::Keyboard,
+            (0, _) => Self::Wasd,
+            (1, _) => Self::Arrows,
+            (index, _) => Self::Gamepad(index - 2),
+        }
+    }
+}
+
+/// Tints that tell players apart.
+const PLAYER_COLORS: [Color; LocalPlayers::MAX] = [
+    Color::WHITE,
+    Color::srgb(0.6, 0.8, 1.0),
+    Color::srgb(0.6, 1.0, 0.6),
+    Color::srgb(1.0, 0.65, 0.8),
+];
+
+/// Where the player entered the level. They are sent back here when they fall
+/// into a pit before reaching a checkpoint.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct SpawnPoint(pub Vec2);
+
+const PLAYER_HEALTH: u32 = 5;
 
 fn spawn_player(
-    _trigger: Trigger<SpawnPlayer>,
+    trigger: Trigger<SpawnPlayer>,
     mut commands: Commands,
-    image_handles: Res<HandleMap<ImageKey>>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    sprite_sheet_handles: Res<HandleMap<SpriteSheetKey>>,
+    sprite_sheets: Res<Assets<SpriteSheet>>,
+    local_players: Res<LocalPlayers>,
 ) {
-    // A texture atlas is a way to split one image with a grid into multiple sprites.
+    // A texture atlas is a way to split one image into multiple sprites.
     // By attaching it to a [`SpriteBundle`] and providing an index, we can specify which section of the image we want to see.
     // We will use this to animate our player character. You can learn more about texture atlases in this example:
     // https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 2, Some(UVec2::splat(1)), None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let player_animation = PlayerAnimation::new();
-
-    commands.spawn((
-        Name::new("Player"),
-        Player,
-        SpriteBundle {
-            texture: image_handles[&ImageKey::Ducky].clone_weak(),
-            transform: Transform::from_scale(Vec2::splat(8.0).extend(1.0)),
-            ..Default::default()
-        },
-        TextureAtlas {
-            layout: texture_atlas_layout.clone(),
-            index: player_animation.get_atlas_index(),
-        },
-        MovementController::default(),
-        Movement { speed: 420.0 },
-        WrapWithinWindow,
-        player_animation,
-        StateScoped(Screen::Playing),
-    ));
+    // The layout and animations of the sheet are exported from Aseprite.
+    let Some(sheet) = sprite_sheets.get(&sprite_sheet_handles[&SpriteSheetKey::Ducky]) else {
+        error!("Tried to spawn a player before their sprite sheet was loaded");
+        return;
+    };
+    let player_animation = SpriteAnimation::new(sheet.animations.clone(), "idle");
+
+    let event = trigger.event();
+    let local_players = local_players.count.max(1);
+    let color = PLAYER_COLORS[event.index % PLAYER_COLORS.len()];
+    commands
+        .spawn((
+            Name::new(format!("Player {}", event.index + 1)),
+            Player { index: event.index },
+            PlayerControls::for_player(event.index, local_players),
+            SpawnPoint(event.translation),
+            Health::new(PLAYER_HEALTH),
+            SpriteBundle {
+                sprite: Sprite { color, ..default() },
+                texture: sheet.image.clone(),
+                transform: Transform::from_translation(event.translation.extend(0.0))
+                    .with_scale(Vec2::splat(8.0).extend(1.0)),
+                ..Default::default()
+            },
+            TextureAtlas {
+                layout: sheet.layout.clone(),
+                index: 0,
+            },
+            (
+                MovementController::default(),
+                Movement {
+                    speed: 420.0,
+                    ..default()
+                },
+                Facing::default(),
+            ),
+            CameraTarget,
+            MapIcon::new(color).with_size(8.0),
+            TileCollider {
+                half_size: Vec2::splat(48.0),
+            },
+            // Sort by the bottom of the collider, where the ducky's feet are.
+            YSort::new(-48.0),
+            (
+                player_animation,
+                player_state_machine(),
+                // The ducky only has art facing right, which is mirrored to face left.
+                DirectionalAnimation::default(),
+            ),
+            // Juice keeps the scale above as its base and squashes, bobs and punches on top.
+            (
+                Juice::default(),
+                SquashStretch::default(),
+                WalkBob::default(),
+                ScaleSpring::default(),
+            ),
+            StateScoped(Screen::Playing),
+        ))
+        .add(ApplyComponentDefs(event.components.clone()));
 }
@@ -0,0 +1,30 @@
+//! Spawn the tilemap holding a level's geometry.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{layers::DrawLayer, tilemap::Tilemap},
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_tilemap);
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnTilemap {
+    pub tilemap: Tilemap,
+    /// World position of the tilemap's top left corner.
+    pub translation: Vec2,
+}
+
+fn spawn_tilemap(trigger: Trigger<SpawnTilemap>, mut commands: Commands) {
+    let event = trigger.event();
+    commands.spawn((
+        Name::new("Tilemap"),
+        event.tilemap.clone(),
+        SpatialBundle::from_transform(Transform::from_translation(event.translation.extend(0.0))),
+        DrawLayer::Ground,
+        StateScoped(Screen::Playing),
+    ));
+}
@@ -0,0 +1,76 @@
+//! Spawn a trigger volume, switch or pressure plate.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        layers::DrawLayer,
+        level::components::{ApplyComponentDefs, ComponentDef},
+        triggers::TriggerKind,
+    },
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_trigger);
+    app.register_type::<TriggerVolume>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnTrigger {
+    pub name: String,
+    pub kind: TriggerKind,
+    /// World position of the area's center.
+    pub translation: Vec2,
+    pub size: Vec2,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    pub components: Vec<ComponentDef>,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TriggerVolume {
+    pub name: String,
+    pub kind: TriggerKind,
+    pub half_size: Vec2,
+    /// Whether the trigger currently sends an "on" signal.
+    pub on: bool,
+    /// Actors inside the area as of the last update.
+    pub occupants: Vec<Entity>,
+}
+
+fn spawn_trigger(trigger: Trigger<SpawnTrigger>, mut commands: Commands) {
+    let event = trigger.event();
+    let transform = Transform::from_translation(event.translation.extend(0.0));
+    let mut entity = commands.spawn((
+        Name::new(format!("Trigger {}", event.name)),
+        TriggerVolume {
+            name: event.name.clone(),
+            kind: event.kind,
+            half_size: event.size / 2.0,
+            on: false,
+            occupants: Vec::new(),
+        },
+        // Switches and pressure plates lie flat on the ground.
+        DrawLayer::Decals,
+        StateScoped(Screen::Playing),
+    ));
+    // Volumes are invisible, the sprite's color is set by `update_trigger_sprites`.
+    let sprite_size = match event.kind {
+        TriggerKind::Volume => None,
+        TriggerKind::Switch => Some(event.size * 0.4),
+        TriggerKind::PressurePlate => Some(event.size * 0.8),
+    };
+    match sprite_size {
+        Some(size) => entity.insert(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(size),
+                ..default()
+            },
+            transform,
+            ..default()
+        }),
+        None => entity.insert(SpatialBundle::from_transform(transform)),
+    };
+    entity.add(ApplyComponentDefs(event.components.clone()));
+}
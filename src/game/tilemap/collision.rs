@@ -0,0 +1,111 @@
+//! Generate collision shapes from solid tiles and keep colliders out of them.
+
+use bevy::{math::URect, prelude::*};
+
+use super::{Tilemap, CHUNK_SIZE};
+use crate::{game::movement::apply_movement, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(ChunkColliders, TileCollider)>();
+    app.add_systems(
+        Update,
+        collide_with_tiles
+            .after(apply_movement)
+            .in_set(AppSet::Update),
+    );
+}
+
+/// Axis-aligned rectangles covering the solid tiles of a chunk,
+/// relative to the chunk's transform.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct ChunkColliders(pub Vec<Rect>);
+
+impl ChunkColliders {
+    /// Cover the solid tiles of the chunk at `chunk_position` with as few rectangles as possible.
+    /// Horizontal runs of solid tiles are merged with identical runs in the rows below.
+    pub fn from_tilemap(tilemap: &Tilemap, chunk_position: UVec2) -> Self {
+        let origin = chunk_position * CHUNK_SIZE;
+        let is_solid = |x: u32, y: u32| tilemap.is_solid(origin + UVec2::new(x, y));
+
+        let mut closed = Vec::new();
+        let mut open: Vec<URect> = Vec::new();
+        for y in 0..CHUNK_SIZE {
+            let mut next_open = Vec::new();
+            let mut x = 0;
+            while x < CHUNK_SIZE {
+                if !is_solid(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < CHUNK_SIZE && is_solid(x, y) {
+                    x += 1;
+                }
+                let run = match open
+                    .iter()
+                    .position(|rect| rect.min.x == start && rect.max.x == x)
+                {
+                    Some(index) => {
+                        let mut rect = open.swap_remove(index);
+                        rect.max.y = y + 1;
+                        rect
+                    }
+                    None => URect::new(start, y, x, y + 1),
+                };
+                next_open.push(run);
+            }
+            closed.append(&mut open);
+            open = next_open;
+        }
+        closed.append(&mut open);
+
+        let tile_size = tilemap.tile_size();
+        Self(
+            closed
+                .into_iter()
+                .map(|rect| {
+                    let min = rect.min.as_vec2() * tile_size;
+                    let max = rect.max.as_vec2() * tile_size;
+                    Rect::new(min.x, -max.y, max.x, -min.y)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// An axis-aligned box that is pushed out of solid tiles.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct TileCollider {
+    pub half_size: Vec2,
+}
+
+fn collide_with_tiles(
+    chunk_query: Query<(&GlobalTransform, &ChunkColliders)>,
+    mut collider_query: Query<(&TileCollider, &mut Transform)>,
+) {
+    for (collider, mut transform) in &mut collider_query {
+        for (chunk_transform, chunk_colliders) in &chunk_query {
+            let offset = chunk_transform.translation().xy();
+            for rect in &chunk_colliders.0 {
+                let solid = Rect::from_corners(rect.min + offset, rect.max + offset);
+                let body =
+                    Rect::from_center_half_size(transform.translation.xy(), collider.half_size);
+                let overlap = solid.intersect(body);
+                if overlap.is_empty() {
+                    continue;
+                }
+
+                // Push the body out along the axis of least penetration.
+                let overlap = overlap.size();
+                let away = body.center() - solid.center();
+                if overlap.x < overlap.y {
+                    transform.translation.x += overlap.x * away.x.signum();
+                } else {
+                    transform.translation.y += overlap.y * away.y.signum();
+                }
+            }
+        }
+    }
+}
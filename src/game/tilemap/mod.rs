@@ -0,0 +1,166 @@
+//! A chunked tilemap for level geometry.
+//! Tiles are stored as [`TileId`]s in fixed-size chunks. Each chunk is drawn by its own
+//! child entity, so editing a tile at runtime only rebuilds the chunk it belongs to.
+
+pub mod collision;
+mod render;
+pub mod tileset;
+
+use bevy::prelude::*;
+
+pub use self::tileset::TileId;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Tilemap>();
+    app.add_plugins((tileset::plugin, render::plugin, collision::plugin));
+}
+
+/// Width and height of a chunk in tiles.
+pub const CHUNK_SIZE: u32 = 16;
+
+/// A grid of tiles. The tilemap's origin is the top left corner of tile `(0, 0)`,
+/// and tile positions grow to the right and downwards.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct Tilemap {
+    size: UVec2,
+    tile_size: f32,
+    /// Chunks in row-major order.
+    chunks: Vec<TileChunk>,
+}
+
+#[derive(Reflect, Clone, Debug)]
+struct TileChunk {
+    /// Tiles in row-major order.
+    tiles: Vec<TileId>,
+    /// Whether the chunk changed since it was last drawn.
+    dirty: bool,
+    /// The entity drawing this chunk, once it has been spawned.
+    entity: Option<Entity>,
+}
+
+impl Tilemap {
+    /// Create a tilemap of `size` tiles filled with [`TileId::EMPTY`].
+    pub fn new(size: UVec2, tile_size: f32) -> Self {
+        let chunk_count = Self::chunk_count_for(size);
+        let chunks = (0..chunk_count.x * chunk_count.y)
+            .map(|_| TileChunk {
+                tiles: vec![TileId::EMPTY; (CHUNK_SIZE * CHUNK_SIZE) as usize],
+                dirty: true,
+                entity: None,
+            })
+            .collect();
+        Self {
+            size,
+            tile_size,
+            chunks,
+        }
+    }
+
+    /// Size of the tilemap in tiles.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Width and height of a single tile in pixels.
+    pub fn tile_size(&self) -> f32 {
+        self.tile_size
+    }
+
+    /// Number of chunks along each axis.
+    pub fn chunk_count(&self) -> UVec2 {
+        Self::chunk_count_for(self.size)
+    }
+
+    fn chunk_count_for(size: UVec2) -> UVec2 {
+        (size + CHUNK_SIZE - 1) / CHUNK_SIZE
+    }
+
+    pub fn contains(&self, position: UVec2) -> bool {
+        position.cmplt(self.size).all()
+    }
+
+    /// Return the tile at `position`, or [`TileId::EMPTY`] if it is out of bounds.
+    pub fn get(&self, position: UVec2) -> TileId {
+        if !self.contains(position) {
+            return TileId::EMPTY;
+        }
+        let (chunk, index) = self.locate(position);
+        self.chunks[chunk].tiles[index]
+    }
+
+    /// Replace the tile at `position` and return the previous one,
+    /// or `None` if `position` is out of bounds.
+    pub fn set(&mut self, position: UVec2, tile: TileId) -> Option<TileId> {
+        if !self.contains(position) {
+            return None;
+        }
+        let (chunk, index) = self.locate(position);
+        let chunk = &mut self.chunks[chunk];
+        let previous = std::mem::replace(&mut chunk.tiles[index], tile);
+        if previous != tile {
+            chunk.dirty = true;
+        }
+        Some(previous)
+    }
+
+    /// Whether the tile at `position` blocks movement.
+    pub fn is_solid(&self, position: UVec2) -> bool {
+        self.get(position).properties().solid
+    }
+
+    /// Iterate over all tiles together with their position.
+    pub fn iter(&self) -> impl Iterator<Item = (UVec2, TileId)> + '_ {
+        (0..self.size.y).flat_map(move |y| {
+            (0..self.size.x).map(move |x| {
+                let position = UVec2::new(x, y);
+                (position, self.get(position))
+            })
+        })
+    }
+
+    /// Convert a tile position to the local position of the tile's center.
+    pub fn tile_to_local(&self, position: UVec2) -> Vec2 {
+        let center = position.as_vec2() + 0.5;
+        Vec2::new(center.x, -center.y) * self.tile_size
+    }
+
+    /// Convert a local position to the position of the tile containing it,
+    /// or `None` if it lies outside of the tilemap.
+    pub fn local_to_tile(&self, local: Vec2) -> Option<UVec2> {
+        let tile = (Vec2::new(local.x, -local.y) / self.tile_size).floor();
+        if tile.cmplt(Vec2::ZERO).any() {
+            return None;
+        }
+        let tile = tile.as_uvec2();
+        self.contains(tile).then_some(tile)
+    }
+
+    /// Convert a world position to the position of the tile containing it,
+    /// given the tilemap's transform.
+    pub fn world_to_tile(&self, transform: &GlobalTransform, world: Vec2) -> Option<UVec2> {
+        let local = transform
+            .affine()
+            .inverse()
+            .transform_point3(world.extend(0.0));
+        self.local_to_tile(local.xy())
+    }
+
+    /// Return the chunk index and the index within that chunk of the tile at `position`.
+    fn locate(&self, position: UVec2) -> (usize, usize) {
+        let chunk = position / CHUNK_SIZE;
+        let local = position % CHUNK_SIZE;
+        let chunk_index = chunk.y * self.chunk_count().x + chunk.x;
+        let tile_index = local.y * CHUNK_SIZE + local.x;
+        (chunk_index as usize, tile_index as usize)
+    }
+
+    /// Position of the chunk with the given index, in chunks.
+    fn chunk_position(&self, chunk_index: usize) -> UVec2 {
+        let chunk_count = self.chunk_count();
+        UVec2::new(
+            chunk_index as u32 % chunk_count.x,
+            chunk_index as u32 / chunk_count.x,
+        )
+    }
+}
@@ -0,0 +1,92 @@
+//! Draw tilemap chunks as sprites.
+
+use bevy::prelude::*;
+
+use super::{collision::ChunkColliders, tileset::TilesetAtlas, Tilemap, CHUNK_SIZE};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TilemapChunk>();
+    app.add_systems(Update, rebuild_dirty_chunks.in_set(AppSet::Update));
+}
+
+/// A chunk of a [`Tilemap`]. Its children are the sprites of the chunk's tiles.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct TilemapChunk {
+    /// Position of the chunk, in chunks.
+    pub position: UVec2,
+}
+
+/// Respawn the sprites and colliders of every chunk whose tiles changed.
+fn rebuild_dirty_chunks(
+    mut commands: Commands,
+    atlas: Res<TilesetAtlas>,
+    mut tilemap_query: Query<(Entity, &mut Tilemap), Changed<Tilemap>>,
+) {
+    for (tilemap_entity, mut tilemap) in &mut tilemap_query {
+        // Clearing the dirty flags should not mark the tilemap as changed again.
+        let tilemap = tilemap.bypass_change_detection();
+        let tile_size = tilemap.tile_size;
+
+        for chunk_index in 0..tilemap.chunks.len() {
+            if !tilemap.chunks[chunk_index].dirty {
+                continue;
+            }
+            let chunk_position = tilemap.chunk_position(chunk_index);
+            let colliders = ChunkColliders::from_tilemap(tilemap, chunk_position);
+
+            let chunk = &mut tilemap.chunks[chunk_index];
+            chunk.dirty = false;
+            let chunk_entity = *chunk.entity.get_or_insert_with(|| {
+                let origin = (chunk_position * CHUNK_SIZE).as_vec2() * tile_size;
+                let chunk_entity = commands
+                    .spawn((
+                        Name::new("Tilemap Chunk"),
+                        TilemapChunk {
+                            position: chunk_position,
+                        },
+                        SpatialBundle::from_transform(Transform::from_xyz(
+                            origin.x, -origin.y, 0.0,
+                        )),
+                    ))
+                    .id();
+                commands.entity(tilemap_entity).add_child(chunk_entity);
+                chunk_entity
+            });
+
+            commands
+                .entity(chunk_entity)
+                .despawn_descendants()
+                .insert(colliders)
+                .with_children(|children| {
+                    for (index, tile) in chunk.tiles.iter().enumerate() {
+                        let Some(atlas_index) = tile.properties().atlas_index else {
+                            continue;
+                        };
+                        let local =
+                            UVec2::new(index as u32 % CHUNK_SIZE, index as u32 / CHUNK_SIZE);
+                        let center = (local.as_vec2() + 0.5) * tile_size;
+                        let angle = tile.properties().surface.sprite_angle();
+                        children.spawn((
+                            Name::new("Tile"),
+                            SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: Some(Vec2::splat(tile_size)),
+                                    ..default()
+                                },
+                                texture: atlas.texture.clone_weak(),
+                                transform: Transform::from_xyz(center.x, -center.y, 0.0)
+                                    .with_rotation(Quat::from_rotation_z(angle)),
+                                ..default()
+                            },
+                            TextureAtlas {
+                                layout: atlas.layout.clone(),
+                                index: atlas_index,
+                            },
+                        ));
+                    }
+                });
+        }
+    }
+}
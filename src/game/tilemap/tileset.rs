@@ -0,0 +1,229 @@
+//! The tile types a [`Tilemap`](super::Tilemap) can hold and their properties.
+
+use bevy::prelude::*;
+
+use crate::game::assets::{HandleMap, ImageKey};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(TileId, Surface, Hazard)>();
+    app.init_resource::<TilesetAtlas>();
+}
+
+/// Identifies a tile type. Use [`TileId::properties`] to look up what it does.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Reflect)]
+pub struct TileId(pub u16);
+
+impl TileId {
+    pub const EMPTY: Self = Self(0);
+    pub const FLOOR: Self = Self(1);
+    pub const WALL: Self = Self(2);
+    pub const ICE: Self = Self(3);
+    pub const MUD: Self = Self(4);
+    pub const SPIKES: Self = Self(5);
+    pub const PIT: Self = Self(6);
+    pub const DOOR: Self = Self(7);
+    pub const CONVEYOR_RIGHT: Self = Self(8);
+    pub const CONVEYOR_DOWN: Self = Self(9);
+    pub const CONVEYOR_LEFT: Self = Self(10);
+    pub const CONVEYOR_UP: Self = Self(11);
+
+    /// The conveyor tiles in clockwise order.
+    pub const CONVEYORS: [Self; 4] = [
+        Self::CONVEYOR_RIGHT,
+        Self::CONVEYOR_DOWN,
+        Self::CONVEYOR_LEFT,
+        Self::CONVEYOR_UP,
+    ];
+
+    /// Return the properties of this tile type.
+    /// Unknown IDs behave like [`TileId::EMPTY`].
+    pub fn properties(self) -> &'static TileProperties {
+        TILES.get(self.0 as usize).unwrap_or(&TILES[0])
+    }
+
+    /// Look up the tile type represented by `symbol` in level files.
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        TILES
+            .iter()
+            .position(|tile| tile.symbol == symbol)
+            .map(|index| Self(index as u16))
+    }
+
+    /// Iterate over all known tile types.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..TILES.len()).map(|index| Self(index as u16))
+    }
+}
+
+/// Static description of a tile type.
+#[derive(Debug)]
+pub struct TileProperties {
+    pub name: &'static str,
+    /// The character representing this tile in level files.
+    pub symbol: char,
+    /// Index into the tileset texture atlas, or `None` if nothing should be drawn.
+    pub atlas_index: Option<usize>,
+    /// Whether the tile blocks movement.
+    pub solid: bool,
+    pub hazard: Option<Hazard>,
+    pub surface: Surface,
+}
+
+/// How a tile affects movement across it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Reflect)]
+pub enum Surface {
+    #[default]
+    Normal,
+    /// Slippery, so changing direction takes a while.
+    Ice,
+    /// Slows down movement.
+    Mud,
+    /// Pushes everything on it towards `direction`, given in tile coordinates.
+    Conveyor { direction: IVec2 },
+}
+
+impl Surface {
+    /// Rotation of the tile's sprite. Conveyor sprites point to the right.
+    pub fn sprite_angle(self) -> f32 {
+        match self {
+            Self::Conveyor { direction } => {
+                Vec2::new(direction.x as f32, -direction.y as f32).to_angle()
+            }
+            Self::Normal | Self::Ice | Self::Mud => 0.0,
+        }
+    }
+}
+
+/// How a tile hurts whoever steps on it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Reflect)]
+pub enum Hazard {
+    Spikes,
+    Pit,
+}
+
+/// All tile types, indexed by [`TileId`].
+const TILES: &[TileProperties] = &[
+    TileProperties {
+        name: "Empty",
+        symbol: ' ',
+        atlas_index: None,
+        solid: false,
+        hazard: None,
+        surface: Surface::Normal,
+    },
+    TileProperties {
+        name: "Floor",
+        symbol: '.',
+        atlas_index: Some(0),
+        solid: false,
+        hazard: None,
+        surface: Surface::Normal,
+    },
+    TileProperties {
+        name: "Wall",
+        symbol: '#',
+        atlas_index: Some(1),
+        solid: true,
+        hazard: None,
+        surface: Surface::Normal,
+    },
+    TileProperties {
+        name: "Ice",
+        symbol: '~',
+        atlas_index: Some(2),
+        solid: false,
+        hazard: None,
+        surface: Surface::Ice,
+    },
+    TileProperties {
+        name: "Mud",
+        symbol: ',',
+        atlas_index: Some(3),
+        solid: false,
+        hazard: None,
+        surface: Surface::Mud,
+    },
+    TileProperties {
+        name: "Spikes",
+        symbol: 'x',
+        atlas_index: Some(4),
+        solid: false,
+        hazard: Some(Hazard::Spikes),
+        surface: Surface::Normal,
+    },
+    TileProperties {
+        name: "Pit",
+        symbol: 'o',
+        atlas_index: Some(5),
+        solid: false,
+        hazard: Some(Hazard::Pit),
+        surface: Surface::Normal,
+    },
+    TileProperties {
+        name: "Door",
+        symbol: '+',
+        atlas_index: Some(7),
+        solid: true,
+        hazard: None,
+        surface: Surface::Normal,
+    },
+    TileProperties {
+        name: "Conveyor (Right)",
+        symbol: '>',
+        atlas_index: Some(6),
+        solid: false,
+        hazard: None,
+        surface: Surface::Conveyor {
+            direction: IVec2::X,
+        },
+    },
+    TileProperties {
+        name: "Conveyor (Down)",
+        symbol: 'v',
+        atlas_index: Some(6),
+        solid: false,
+        hazard: None,
+        surface: Surface::Conveyor {
+            direction: IVec2::Y,
+        },
+    },
+    TileProperties {
+        name: "Conveyor (Left)",
+        symbol: '<',
+        atlas_index: Some(6),
+        solid: false,
+        hazard: None,
+        surface: Surface::Conveyor {
+            direction: IVec2::NEG_X,
+        },
+    },
+    TileProperties {
+        name: "Conveyor (Up)",
+        symbol: '^',
+        atlas_index: Some(6),
+        solid: false,
+        hazard: None,
+        surface: Surface::Conveyor {
+            direction: IVec2::NEG_Y,
+        },
+    },
+];
+
+/// The texture atlas used to draw tiles.
+#[derive(Resource, Debug)]
+pub struct TilesetAtlas {
+    pub texture: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+impl FromWorld for TilesetAtlas {
+    fn from_world(world: &mut World) -> Self {
+        let texture = world.resource::<HandleMap<ImageKey>>()[&ImageKey::Tileset].clone_weak();
+        // The tileset is a 4x2 grid of 16x16 tiles, see `assets/images/tileset.png`.
+        let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 4, 2, None, None);
+        let layout = world
+            .resource_mut::<Assets<TextureAtlasLayout>>()
+            .add(layout);
+        Self { texture, layout }
+    }
+}
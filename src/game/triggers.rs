@@ -0,0 +1,260 @@
+//! Trigger volumes, switches and pressure plates, and the signals they send
+//! through the level's wires to doors, the soundtrack or anything observing [`LevelEvent`]s.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::SoundtrackKey,
+    audio::soundtrack::PlaySoundtrack,
+    door::SetDoorOpen,
+    level::components::ComponentDef,
+    spawn::{enemy::Chaser, player::Player, trigger::TriggerVolume},
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Signals>();
+    app.add_systems(
+        Update,
+        (
+            tick_delayed_signals.in_set(AppSet::TickTimers),
+            (detect_occupants, update_trigger_sprites)
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Playing).and_then(resource_exists::<Signals>)),
+    );
+    app.observe(send_signal);
+
+    // Trace level logic to help with debugging levels.
+    app.observe(log_trigger_entered);
+    app.observe(log_trigger_exited);
+    app.observe(log_level_event);
+}
+
+/// An area of the level that reacts to actors entering and leaving it.
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct TriggerDef {
+    /// Wires refer to the trigger by this name.
+    pub name: String,
+    /// The top left tile of the area.
+    pub position: UVec2,
+    /// Size of the area in tiles.
+    #[serde(default = "default_trigger_size")]
+    pub size: UVec2,
+    pub kind: TriggerKind,
+    /// Extra components, e.g. from custom properties in a Tiled map.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[reflect(ignore)]
+    pub components: Vec<ComponentDef>,
+}
+
+fn default_trigger_size() -> UVec2 {
+    UVec2::ONE
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum TriggerKind {
+    /// An invisible area that is on while the player is inside.
+    Volume,
+    /// Turns on or off each time the player steps onto it.
+    Switch,
+    /// On while the player or a chaser stands on it.
+    PressurePlate,
+}
+
+/// Connects a trigger to something it controls.
+/// When the trigger turns on or off, the signal arrives at the target after `delay_secs`.
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct WireDef {
+    /// Name of the trigger sending the signal.
+    pub from: String,
+    pub to: WireTarget,
+    #[serde(default)]
+    pub delay_secs: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum WireTarget {
+    /// Open the doors with this name while the signal is on, and close them while it is off.
+    Door(String),
+    /// Switch to this soundtrack when the signal turns on.
+    Soundtrack(SoundtrackKey),
+    /// Trigger a [`LevelEvent`] with this name when the signal turns on, e.g. to start a cutscene.
+    Event(String),
+}
+
+/// Triggered on a [`TriggerVolume`] entity when an actor enters it.
+#[derive(Event, Debug)]
+pub struct TriggerEntered {
+    pub actor: Entity,
+}
+
+/// Triggered on a [`TriggerVolume`] entity when an actor leaves it.
+#[derive(Event, Debug)]
+pub struct TriggerExited {
+    pub actor: Entity,
+}
+
+/// Triggered when a trigger turns on or off.
+#[derive(Event, Debug)]
+pub struct SignalChanged {
+    pub from: String,
+    pub on: bool,
+}
+
+/// Triggered by wires with a [`WireTarget::Event`] target.
+#[derive(Event, Debug)]
+pub struct LevelEvent(pub String);
+
+/// The wires of the current level and the signals travelling through them.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct Signals {
+    wires: Vec<WireDef>,
+    /// Signals waiting for their wire's delay.
+    pending: Vec<PendingSignal>,
+}
+
+#[derive(Reflect, Debug)]
+struct PendingSignal {
+    timer: Timer,
+    target: WireTarget,
+    on: bool,
+}
+
+impl Signals {
+    pub fn new(wires: Vec<WireDef>) -> Self {
+        Self {
+            wires,
+            pending: Vec::new(),
+        }
+    }
+}
+
+fn detect_occupants(
+    mut commands: Commands,
+    mut trigger_query: Query<(Entity, &mut TriggerVolume, &Transform)>,
+    actor_query: Query<(Entity, &Transform, Has<Player>), Or<(With<Player>, With<Chaser>)>>,
+) {
+    for (entity, mut trigger, transform) in &mut trigger_query {
+        let area = Rect::from_center_half_size(transform.translation.xy(), trigger.half_size);
+        let occupants: Vec<Entity> = actor_query
+            .iter()
+            .filter(|(_, actor, is_player)| {
+                (*is_player || trigger.kind == TriggerKind::PressurePlate)
+                    && area.contains(actor.translation.xy())
+            })
+            .map(|(actor, ..)| actor)
+            .collect();
+        if occupants == trigger.occupants {
+            continue;
+        }
+
+        let entered: Vec<Entity> = occupants
+            .iter()
+            .copied()
+            .filter(|actor| !trigger.occupants.contains(actor))
+            .collect();
+        for &actor in &entered {
+            commands.trigger_targets(TriggerEntered { actor }, entity);
+        }
+        for &actor in trigger.occupants.iter().filter(|a| !occupants.contains(a)) {
+            commands.trigger_targets(TriggerExited { actor }, entity);
+        }
+
+        let on = match trigger.kind {
+            TriggerKind::Switch => trigger.on == entered.is_empty(),
+            TriggerKind::Volume | TriggerKind::PressurePlate => !occupants.is_empty(),
+        };
+        trigger.occupants = occupants;
+        if on != trigger.on {
+            trigger.on = on;
+            commands.trigger(SignalChanged {
+                from: trigger.name.clone(),
+                on,
+            });
+        }
+    }
+}
+
+const TRIGGER_OFF_COLOR: Color = Color::srgb(0.420, 0.420, 0.459);
+const TRIGGER_ON_COLOR: Color = Color::srgb(0.878, 0.780, 0.329);
+
+fn update_trigger_sprites(
+    mut trigger_query: Query<(&TriggerVolume, &mut Sprite), Changed<TriggerVolume>>,
+) {
+    for (trigger, mut sprite) in &mut trigger_query {
+        sprite.color = if trigger.on {
+            TRIGGER_ON_COLOR
+        } else {
+            TRIGGER_OFF_COLOR
+        };
+    }
+}
+
+fn send_signal(
+    trigger: Trigger<SignalChanged>,
+    mut commands: Commands,
+    signals: Option<ResMut<Signals>>,
+) {
+    let Some(mut signals) = signals else {
+        return;
+    };
+    let event = trigger.event();
+    let signals = &mut *signals;
+    for wire in signals.wires.iter().filter(|wire| wire.from == event.from) {
+        if wire.delay_secs > 0.0 {
+            signals.pending.push(PendingSignal {
+                timer: Timer::from_seconds(wire.delay_secs, TimerMode::Once),
+                target: wire.to.clone(),
+                on: event.on,
+            });
+        } else {
+            deliver_signal(&mut commands, &wire.to, event.on);
+        }
+    }
+}
+
+fn tick_delayed_signals(mut commands: Commands, time: Res<Time>, mut signals: ResMut<Signals>) {
+    signals.pending.retain_mut(|signal| {
+        signal.timer.tick(time.delta());
+        if signal.timer.finished() {
+            deliver_signal(&mut commands, &signal.target, signal.on);
+        }
+        !signal.timer.finished()
+    });
+}
+
+fn deliver_signal(commands: &mut Commands, target: &WireTarget, on: bool) {
+    match target {
+        WireTarget::Door(name) => commands.trigger(SetDoorOpen {
+            name: name.clone(),
+            open: on,
+        }),
+        WireTarget::Soundtrack(key) if on => commands.trigger(PlaySoundtrack::Key(*key)),
+        WireTarget::Event(name) if on => commands.trigger(LevelEvent(name.clone())),
+        WireTarget::Soundtrack(_) | WireTarget::Event(_) => {}
+    }
+}
+
+fn log_trigger_entered(trigger: Trigger<TriggerEntered>, trigger_query: Query<&TriggerVolume>) {
+    if let Ok(volume) = trigger_query.get(trigger.entity()) {
+        debug!(
+            "{} entered trigger {:?}",
+            trigger.event().actor,
+            volume.name
+        );
+    }
+}
+
+fn log_trigger_exited(trigger: Trigger<TriggerExited>, trigger_query: Query<&TriggerVolume>) {
+    if let Ok(volume) = trigger_query.get(trigger.entity()) {
+        debug!("{} left trigger {:?}", trigger.event().actor, volume.name);
+    }
+}
+
+fn log_level_event(trigger: Trigger<LevelEvent>) {
+    debug!("Level event {:?}", trigger.event().0);
+}
@@ -1,14 +1,22 @@
 #[cfg(feature = "dev")]
 mod dev_tools;
+#[cfg(feature = "dev")]
+mod editor;
 mod game;
 mod screen;
+pub mod tween;
 mod ui;
 
 use bevy::{
     asset::AssetMetaCheck,
     audio::{AudioPlugin, Volume},
     prelude::*,
+    render::{camera::ClearColorConfig, view::RenderLayers},
 };
+use game::camera::{shake::CameraShake, split::SplitViewport, CameraFollow};
+
+// Expose level generation for the `generate_level` example.
+pub use game::level::procgen;
 
 pub struct AppPlugin;
 
@@ -53,11 +61,11 @@ impl Plugin for AppPlugin {
         );
 
         // Add other plugins.
-        app.add_plugins((game::plugin, screen::plugin, ui::plugin));
+        app.add_plugins((game::plugin, screen::plugin, tween::plugin, ui::plugin));
 
         // Enable dev tools for dev builds.
         #[cfg(feature = "dev")]
-        app.add_plugins(dev_tools::plugin);
+        app.add_plugins((dev_tools::plugin, editor::plugin));
     }
 }
 
@@ -78,12 +86,24 @@ fn spawn_camera(mut commands: Commands) {
     commands.spawn((
         Name::new("Camera"),
         Camera2dBundle::default(),
-        // Render all UI to this camera.
-        // Not strictly necessary since we only use one camera,
-        // but if we don't use this component, our UI will disappear as soon
-        // as we add another camera. This includes indirect ways of adding cameras like using
-        // [ui node outlines](https://bevyengine.org/news/bevy-0-14/#ui-node-outline-gizmos)
-        // for debugging. So it's good to have this here for future-proofing.
+        CameraFollow::default(),
+        CameraShake::default(),
+        SplitViewport::default(),
+    ));
+    commands.spawn((
+        Name::new("UI Camera"),
+        Camera2dBundle {
+            camera: Camera {
+                // Draw the UI on top of every view in split-screen.
+                order: 10,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            ..default()
+        },
+        // Don't draw the world a second time.
+        RenderLayers::none(),
+        // Render all UI to this camera, since there can be several cameras for the world.
         IsDefaultUiCamera,
     ));
 }
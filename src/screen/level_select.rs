@@ -0,0 +1,140 @@
+//! A screen listing the campaign's levels, from which unlocked levels can be played.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::{
+        assets::{CampaignKey, HandleMap},
+        level::{campaign::Campaign, CurrentLevel, LevelDef, LevelId},
+        progress::Progress,
+        spawn::player::LocalPlayers,
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::LevelSelect), enter_level_select);
+
+    app.register_type::<LevelSelectAction>();
+    app.add_systems(
+        Update,
+        (
+            limit_local_players,
+            handle_level_select_action,
+            update_players_label,
+        )
+            .chain()
+            .run_if(in_state(Screen::LevelSelect)),
+    );
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum LevelSelectAction {
+    Play(LevelId),
+    /// Cycle through the number of [`LocalPlayers`] that have controls.
+    TogglePlayers,
+    Back,
+}
+
+fn players_label(local_players: &LocalPlayers) -> String {
+    format!("Players: {}", local_players.count)
+}
+
+fn enter_level_select(
+    mut commands: Commands,
+    campaign_handles: Res<HandleMap<CampaignKey>>,
+    campaigns: Res<Assets<Campaign>>,
+    level_defs: Res<Assets<LevelDef>>,
+    progress: Res<Progress>,
+    local_players: Res<LocalPlayers>,
+) {
+    let Some(campaign) = campaigns.get(&campaign_handles[&CampaignKey::Main]) else {
+        return;
+    };
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::LevelSelect))
+        .with_children(|children| {
+            children.header("Select Level");
+
+            for (index, level) in campaign.levels.iter().enumerate() {
+                let name = level_defs
+                    .get(&level.level)
+                    .map_or(level.id.0.as_str(), |level_def| level_def.name.as_str());
+                if !campaign.is_unlocked(index, &progress) {
+                    children.label(format!("{name} - Locked"));
+                    continue;
+                }
+
+                children
+                    .button(name)
+                    .insert(LevelSelectAction::Play(level.id.clone()));
+                let status = match progress.best_score(&level.id) {
+                    None => "Not completed".to_string(),
+                    Some(score) => match level.medals.medal(score) {
+                        Some(medal) => format!("Best: {score} - {} medal", medal.name()),
+                        None => format!("Best: {score}"),
+                    },
+                };
+                children.label(status);
+            }
+
+            children
+                .button(players_label(&local_players))
+                .insert(LevelSelectAction::TogglePlayers);
+            children.button("Back").insert(LevelSelectAction::Back);
+        });
+}
+
+fn handle_level_select_action(
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut local_players: ResMut<LocalPlayers>,
+    gamepads: Res<Gamepads>,
+    mut button_query: InteractionQuery<&LevelSelectAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                LevelSelectAction::Play(id) => {
+                    commands.insert_resource(CurrentLevel(id.clone()));
+                    next_screen.set(Screen::Playing);
+                }
+                LevelSelectAction::TogglePlayers => {
+                    let max = LocalPlayers::max_controllable(&gamepads);
+                    local_players.count = local_players.count % max + 1;
+                }
+                LevelSelectAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
+
+/// Drop players without controls, e.g. when their gamepad disconnects.
+fn limit_local_players(gamepads: Res<Gamepads>, mut local_players: ResMut<LocalPlayers>) {
+    let max = LocalPlayers::max_controllable(&gamepads);
+    if local_players.count > max {
+        local_players.count = max;
+    }
+}
+
+fn update_players_label(
+    local_players: Res<LocalPlayers>,
+    button_query: Query<(&LevelSelectAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !local_players.is_changed() {
+        return;
+    }
+    for (action, children) in &button_query {
+        if *action != LevelSelectAction::TogglePlayers {
+            continue;
+        }
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = players_label(&local_players);
+        }
+    }
+}
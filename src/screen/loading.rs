@@ -5,7 +5,9 @@ use bevy::prelude::*;
 
 use super::Screen;
 use crate::{
-    game::assets::{HandleMap, ImageKey, SfxKey, SoundtrackKey},
+    game::assets::{
+        CampaignKey, HandleMap, ImageKey, ParticleKey, SfxKey, SoundtrackKey, SpriteSheetKey,
+    },
     ui::prelude::*,
 };
 
@@ -13,10 +15,15 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Loading), enter_loading);
     app.add_systems(
         Update,
-        continue_to_title.run_if(in_state(Screen::Loading).and_then(all_assets_loaded)),
+        continue_to_next_screen.run_if(in_state(Screen::Loading).and_then(all_assets_loaded)),
     );
 }
 
+/// The screen to continue to once loading has finished, instead of [`Screen::Title`].
+/// It is used up by the next loading screen.
+#[derive(Resource, Debug)]
+pub(super) struct ContinueTo(pub Screen);
+
 fn enter_loading(mut commands: Commands) {
     commands
         .ui_root()
@@ -31,12 +38,28 @@ fn all_assets_loaded(
     image_handles: Res<HandleMap<ImageKey>>,
     sfx_handles: Res<HandleMap<SfxKey>>,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    campaign_handles: Res<HandleMap<CampaignKey>>,
+    sprite_sheet_handles: Res<HandleMap<SpriteSheetKey>>,
+    particle_handles: Res<HandleMap<ParticleKey>>,
 ) -> bool {
     image_handles.all_loaded(&asset_server)
         && sfx_handles.all_loaded(&asset_server)
         && soundtrack_handles.all_loaded(&asset_server)
+        && campaign_handles.all_loaded(&asset_server)
+        && sprite_sheet_handles.all_loaded(&asset_server)
+        && particle_handles.all_loaded(&asset_server)
 }
 
-fn continue_to_title(mut next_screen: ResMut<NextState<Screen>>) {
-    next_screen.set(Screen::Title);
+fn continue_to_next_screen(
+    mut commands: Commands,
+    continue_to: Option<Res<ContinueTo>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    match continue_to {
+        Some(continue_to) => {
+            next_screen.set(continue_to.0.clone());
+            commands.remove_resource::<ContinueTo>();
+        }
+        None => next_screen.set(Screen::Title),
+    }
 }
@@ -1,8 +1,11 @@
 //! The game's main screen states and transitions between them.
 
 mod credits;
+mod level_select;
 mod loading;
 mod playing;
+mod results;
+mod settings;
 mod splash;
 mod title;
 
@@ -17,7 +20,10 @@ pub(super) fn plugin(app: &mut App) {
         loading::plugin,
         title::plugin,
         credits::plugin,
+        settings::plugin,
+        level_select::plugin,
         playing::plugin,
+        results::plugin,
     ));
 }
 
@@ -29,5 +35,11 @@ pub enum Screen {
     Loading,
     Title,
     Credits,
+    Settings,
+    LevelSelect,
     Playing,
+    Results,
+    /// The level editor, see [`crate::editor`].
+    #[cfg(feature = "dev")]
+    Editor,
 }
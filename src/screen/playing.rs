@@ -2,25 +2,40 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use super::Screen;
-use crate::game::{
-    assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack, spawn::level::SpawnLevel,
+use super::{loading::ContinueTo, Screen};
+use crate::{
+    game::{
+        audio::soundtrack::PlaySoundtrack,
+        health::Health,
+        level::{CurrentLevel, LevelDef, Levels},
+        objectives::Objectives,
+        spawn::{level::SpawnLevel, player::Player},
+    },
+    ui::prelude::*,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Playing), enter_playing);
     app.add_systems(OnExit(Screen::Playing), exit_playing);
+    app.add_systems(Update, restart_modified_level);
 
     app.add_systems(
         Update,
         return_to_title_screen
             .run_if(in_state(Screen::Playing).and_then(input_just_pressed(KeyCode::Escape))),
     );
+
+    app.register_type::<ObjectivesHud>();
+    app.add_systems(
+        Update,
+        update_objectives_hud
+            .run_if(in_state(Screen::Playing).and_then(resource_exists_and_changed::<Objectives>)),
+    );
 }
 
-fn enter_playing(mut commands: Commands) {
-    commands.trigger(SpawnLevel);
-    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Gameplay));
+fn enter_playing(mut commands: Commands, current_level: Res<CurrentLevel>) {
+    commands.trigger(SpawnLevel(current_level.0.clone()));
+    spawn_objectives_hud(&mut commands);
 }
 
 fn exit_playing(mut commands: Commands) {
@@ -28,6 +43,90 @@ fn exit_playing(mut commands: Commands) {
     commands.trigger(PlaySoundtrack::Disable);
 }
 
+/// Restart the level when its file changes, so that edits made e.g. in Tiled show up right away.
+/// Only native dev builds watch files for changes.
+/// Changing to the state we're already in doesn't leave and re-enter it,
+/// so the level restarts by way of the loading screen.
+fn restart_modified_level(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelDef>>,
+    screen: Res<State<Screen>>,
+    current_level: Res<CurrentLevel>,
+    levels: Res<Levels>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(handle) = levels.get(&current_level.0) else {
+        events.clear();
+        return;
+    };
+    let modified = events.read().any(|event| event.is_modified(handle.id()));
+    if modified && *screen.get() == Screen::Playing {
+        commands.insert_resource(ContinueTo(Screen::Playing));
+        next_screen.set(Screen::Loading);
+    }
+}
+
 fn return_to_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
+
+/// Marker for the text listing the current level's objectives and the player's health.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ObjectivesHud;
+
+fn spawn_objectives_hud(commands: &mut Commands) {
+    commands.spawn((
+        Name::new("Objectives HUD"),
+        ObjectivesHud,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 24.0,
+                color: ui_palette::LABEL_TEXT,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        }),
+        StateScoped(Screen::Playing),
+    ));
+}
+
+// `Objectives` changes every frame while its timer runs, so this also keeps health up to date.
+fn update_objectives_hud(
+    objectives: Res<Objectives>,
+    player_query: Query<(&Player, &Health)>,
+    mut hud_query: Query<&mut Text, With<ObjectivesHud>>,
+) {
+    let mut lines: Vec<String> = objectives
+        .objectives
+        .iter()
+        .map(|objective| {
+            let check = if objective.is_complete() { "x" } else { " " };
+            format!("[{check}] {}", objective.describe())
+        })
+        .collect();
+    if let Some(remaining) = objectives.remaining_secs() {
+        lines.push(format!("Time left: {}s", remaining.ceil()));
+    }
+    let mut players: Vec<_> = player_query.iter().collect();
+    players.sort_by_key(|(player, _)| player.index);
+    let co_op = players.len() > 1;
+    for (player, health) in players {
+        let name = if co_op {
+            format!("P{} health", player.index + 1)
+        } else {
+            "Health".to_string()
+        };
+        lines.push(format!("{name}: {}/{}", health.current, health.max));
+    }
+
+    for mut text in &mut hud_query {
+        text.sections[0].value = lines.join("\n");
+    }
+}
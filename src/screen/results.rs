@@ -0,0 +1,116 @@
+//! The screen shown after a level is completed or failed.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::{
+        assets::{CampaignKey, HandleMap},
+        level::{campaign::Campaign, CurrentLevel, FailReason, LevelCompleted, LevelFailed},
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(show_completed);
+    app.observe(show_failed);
+    app.add_systems(OnEnter(Screen::Results), enter_results);
+
+    app.register_type::<(LevelResult, ResultsAction)>();
+    app.add_systems(
+        Update,
+        handle_results_action.run_if(in_state(Screen::Results)),
+    );
+}
+
+/// How the last level ended.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+enum LevelResult {
+    Completed { score: u32 },
+    Failed(FailReason),
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum ResultsAction {
+    Retry,
+    Continue,
+}
+
+fn show_completed(
+    trigger: Trigger<LevelCompleted>,
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    commands.insert_resource(LevelResult::Completed {
+        score: trigger.event().score,
+    });
+    next_screen.set(Screen::Results);
+}
+
+fn show_failed(
+    trigger: Trigger<LevelFailed>,
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    commands.insert_resource(LevelResult::Failed(trigger.event().reason));
+    next_screen.set(Screen::Results);
+}
+
+fn enter_results(
+    mut commands: Commands,
+    result: Res<LevelResult>,
+    current_level: Res<CurrentLevel>,
+    campaign_handles: Res<HandleMap<CampaignKey>>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    let medals = campaigns
+        .get(&campaign_handles[&CampaignKey::Main])
+        .and_then(|campaign| {
+            campaign
+                .levels
+                .iter()
+                .find(|level| level.id == current_level.0)
+        })
+        .map(|level| level.medals);
+
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Results))
+        .with_children(|children| match *result {
+            LevelResult::Completed { score } => {
+                children.header("Level Complete!");
+                children.label(format!("Score: {score}"));
+                if let Some(medal) = medals.and_then(|medals| medals.medal(score)) {
+                    children.label(format!("{} medal", medal.name()));
+                }
+                children.button("Continue").insert(ResultsAction::Continue);
+                children.button("Replay").insert(ResultsAction::Retry);
+            }
+            LevelResult::Failed(reason) => {
+                children.header("Level Failed");
+                children.label(match reason {
+                    FailReason::Caught => "A chaser caught you!",
+                    FailReason::Killed => "You ran out of health!",
+                    FailReason::TimeUp => "Time ran out!",
+                });
+                children.button("Retry").insert(ResultsAction::Retry);
+                children.button("Give up").insert(ResultsAction::Continue);
+            }
+        });
+}
+
+fn handle_results_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&ResultsAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                ResultsAction::Retry => next_screen.set(Screen::Playing),
+                ResultsAction::Continue => next_screen.set(Screen::LevelSelect),
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! A settings screen that can be accessed from the title screen.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{game::settings::Settings, ui::prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Settings), enter_settings);
+
+    app.register_type::<SettingsAction>();
+    app.add_systems(
+        Update,
+        (handle_settings_action, update_setting_labels)
+            .chain()
+            .run_if(in_state(Screen::Settings)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SettingsAction {
+    ToggleScreenShake,
+    ToggleHitStop,
+    Back,
+}
+
+impl SettingsAction {
+    /// The button text showing the current value of the setting.
+    fn label(self, settings: &Settings) -> String {
+        match self {
+            Self::ToggleScreenShake => format!("Screen shake: {}", settings.screen_shake),
+            Self::ToggleHitStop => format!("Hit stop: {}", settings.hit_stop),
+            Self::Back => "Back".to_string(),
+        }
+    }
+}
+
+fn enter_settings(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Settings))
+        .with_children(|children| {
+            children.header("Accessibility");
+            for action in [
+                SettingsAction::ToggleScreenShake,
+                SettingsAction::ToggleHitStop,
+            ] {
+                children
+                    .button(action.label(&settings))
+                    .insert(action)
+                    .insert(Style {
+                        width: Val::Px(500.0),
+                        height: Val::Px(65.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    });
+            }
+
+            children.button("Back").insert(SettingsAction::Back);
+        });
+}
+
+fn handle_settings_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut settings: ResMut<Settings>,
+    mut button_query: InteractionQuery<&SettingsAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                SettingsAction::ToggleScreenShake => {
+                    settings.screen_shake = settings.screen_shake.next();
+                }
+                SettingsAction::ToggleHitStop => settings.hit_stop = settings.hit_stop.next(),
+                SettingsAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
+
+fn update_setting_labels(
+    settings: Res<Settings>,
+    button_query: Query<(&SettingsAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (action, children) in &button_query {
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = action.label(&settings);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Lenses pick what a tween changes on a component.
+
+use bevy::{prelude::*, reflect::GetPath};
+
+/// Moves part of a `T` between a start and an end value.
+pub trait Lens<T>: Send + Sync + 'static {
+    /// Set the value `ratio` of the way from the start to the end.
+    /// The ratio is eased, so it can be slightly below 0 or above 1.
+    fn lerp(&mut self, target: &mut T, ratio: f32);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformPositionLens {
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+impl Lens<Transform> for TransformPositionLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        target.translation = self.start.lerp(self.end, ratio);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformScaleLens {
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+impl Lens<Transform> for TransformScaleLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        target.scale = self.start.lerp(self.end, ratio);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformRotationLens {
+    pub start: Quat,
+    pub end: Quat,
+}
+
+impl Lens<Transform> for TransformRotationLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        target.rotation = self.start.slerp(self.end, ratio);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteColorLens {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl Lens<Sprite> for SpriteColorLens {
+    fn lerp(&mut self, target: &mut Sprite, ratio: f32) {
+        target.color = self.start.mix(&self.end, ratio);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UiImageColorLens {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl Lens<UiImage> for UiImageColorLens {
+    fn lerp(&mut self, target: &mut UiImage, ratio: f32) {
+        target.color = self.start.mix(&self.end, ratio);
+    }
+}
+
+/// Changes the width and height of a UI node.
+/// Sizes in different units jump to the end instead of moving smoothly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StyleSizeLens {
+    /// Width and height.
+    pub start: [Val; 2],
+    pub end: [Val; 2],
+}
+
+impl Lens<Style> for StyleSizeLens {
+    fn lerp(&mut self, target: &mut Style, ratio: f32) {
+        target.width = lerp_val(self.start[0], self.end[0], ratio);
+        target.height = lerp_val(self.start[1], self.end[1], ratio);
+    }
+}
+
+fn lerp_val(start: Val, end: Val, ratio: f32) -> Val {
+    let lerp = |a: f32, b: f32| a.lerp(b, ratio);
+    match (start, end) {
+        (Val::Px(a), Val::Px(b)) => Val::Px(lerp(a, b)),
+        (Val::Percent(a), Val::Percent(b)) => Val::Percent(lerp(a, b)),
+        (Val::Vw(a), Val::Vw(b)) => Val::Vw(lerp(a, b)),
+        (Val::Vh(a), Val::Vh(b)) => Val::Vh(lerp(a, b)),
+        (Val::VMin(a), Val::VMin(b)) => Val::VMin(lerp(a, b)),
+        (Val::VMax(a), Val::VMax(b)) => Val::VMax(lerp(a, b)),
+        _ if ratio < 1.0 => start,
+        _ => end,
+    }
+}
+
+/// A value of a reflected field that can be tweened.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TweenValue {
+    F32(f32),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Color(Color),
+}
+
+/// Changes any reflected field of a component, found by its
+/// [reflection path](bevy::reflect::GetPath), e.g. `speed` or `color`.
+/// Does nothing if the path doesn't lead to a field of the start value's type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflectLens {
+    pub path: String,
+    pub start: TweenValue,
+    pub end: TweenValue,
+}
+
+impl ReflectLens {
+    pub fn new(path: impl Into<String>, start: TweenValue, end: TweenValue) -> Self {
+        Self {
+            path: path.into(),
+            start,
+            end,
+        }
+    }
+}
+
+impl<T: Reflect> Lens<T> for ReflectLens {
+    fn lerp(&mut self, target: &mut T, ratio: f32) {
+        let Ok(field) = target.reflect_path_mut(self.path.as_str()) else {
+            return;
+        };
+        match (self.start, self.end) {
+            (TweenValue::F32(start), TweenValue::F32(end)) => {
+                if let Some(value) = field.downcast_mut::<f32>() {
+                    *value = start.lerp(end, ratio);
+                }
+            }
+            (TweenValue::Vec2(start), TweenValue::Vec2(end)) => {
+                if let Some(value) = field.downcast_mut::<Vec2>() {
+                    *value = start.lerp(end, ratio);
+                }
+            }
+            (TweenValue::Vec3(start), TweenValue::Vec3(end)) => {
+                if let Some(value) = field.downcast_mut::<Vec3>() {
+                    *value = start.lerp(end, ratio);
+                }
+            }
+            (TweenValue::Color(start), TweenValue::Color(end)) => {
+                if let Some(value) = field.downcast_mut::<Color>() {
+                    *value = start.mix(&end, ratio);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn transform_rotation_lens_interpolates_rotation() {
+        let mut lens = TransformRotationLens {
+            start: Quat::IDENTITY,
+            end: Quat::from_rotation_z(FRAC_PI_2),
+        };
+        let mut transform = Transform::default();
+        lens.lerp(&mut transform, 0.5);
+        let expected = Quat::from_rotation_z(FRAC_PI_2 / 2.0);
+        assert!(transform.rotation.abs_diff_eq(expected, 1e-6));
+    }
+
+    #[test]
+    fn sprite_color_lens_mixes_colors() {
+        let mut lens = SpriteColorLens {
+            start: Color::BLACK,
+            end: Color::WHITE,
+        };
+        let mut sprite = Sprite::default();
+        lens.lerp(&mut sprite, 0.5);
+        assert_eq!(sprite.color, Color::BLACK.mix(&Color::WHITE, 0.5));
+    }
+
+    #[test]
+    fn style_size_lens_jumps_between_units() {
+        let mut lens = StyleSizeLens {
+            start: [Val::Px(0.0), Val::Percent(0.0)],
+            end: [Val::Px(100.0), Val::Px(50.0)],
+        };
+        let mut style = Style::default();
+        lens.lerp(&mut style, 0.5);
+        assert_eq!(style.width, Val::Px(50.0));
+        assert_eq!(style.height, Val::Percent(0.0));
+        lens.lerp(&mut style, 1.0);
+        assert_eq!(style.height, Val::Px(50.0));
+    }
+
+    #[test]
+    fn reflect_lens_changes_fields_of_matching_type() {
+        let mut lens =
+            ReflectLens::new("translation.y", TweenValue::F32(0.0), TweenValue::F32(8.0));
+        let mut transform = Transform::default();
+        lens.lerp(&mut transform, 0.25);
+        assert_eq!(transform.translation, Vec3::new(0.0, 2.0, 0.0));
+
+        let mut lens = ReflectLens::new(
+            "scale",
+            TweenValue::Vec3(Vec3::ONE),
+            TweenValue::Vec3(Vec3::ZERO),
+        );
+        lens.lerp(&mut transform, 0.5);
+        assert_eq!(transform.scale, Vec3::splat(0.5));
+    }
+
+    #[test]
+    fn reflect_lens_ignores_mismatched_paths() {
+        let mut transform = Transform::default();
+        let mut wrong_type = ReflectLens::new("scale", TweenValue::F32(0.0), TweenValue::F32(1.0));
+        wrong_type.lerp(&mut transform, 0.5);
+        let mut missing =
+            ReflectLens::new("nonexistent", TweenValue::F32(0.0), TweenValue::F32(1.0));
+        missing.lerp(&mut transform, 0.5);
+        assert_eq!(transform, Transform::default());
+    }
+}
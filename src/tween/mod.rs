@@ -0,0 +1,373 @@
+//! Tweens animate components over time, e.g. to fade an image or move a sprite.
+//! A [`Tweenable`] combines tweens with delays into sequences and parallel groups,
+//! and an [`Animator`] plays it on an entity's component, optionally repeating it.
+//! Add [`animate`] as a plugin for every component type that should be tweened.
+
+pub mod ease;
+pub mod lens;
+
+use std::ops::DerefMut;
+
+use bevy::prelude::*;
+
+use self::{ease::EaseFunction, lens::Lens};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        animate::<Transform>,
+        animate::<Sprite>,
+        animate::<UiImage>,
+        animate::<Style>,
+    ));
+}
+
+/// Play [`Animator<T>`]s on components of type `T`.
+/// Each component type can only be added once.
+pub fn animate<T: Component>(app: &mut App) {
+    app.add_systems(Update, advance_animators::<T>.in_set(AppSet::Update));
+}
+
+/// Triggered on an entity when one of its [`Animator`]s has finished,
+/// right before the animator is removed.
+#[derive(Event, Debug)]
+pub struct TweenCompleted;
+
+/// Tweens and delays, combined into sequences and parallel groups.
+pub enum Tweenable<T> {
+    /// Moves a [`Lens`] from its start to its end.
+    Tween {
+        duration_secs: f32,
+        ease: EaseFunction,
+        lens: Box<dyn Lens<T>>,
+    },
+    /// Does nothing for a while, e.g. between two steps of a sequence.
+    Delay(f32),
+    /// Plays one after another.
+    Sequence(Vec<Tweenable<T>>),
+    /// Plays all at once, until the longest one has finished.
+    Parallel(Vec<Tweenable<T>>),
+}
+
+impl<T: 'static> Tweenable<T> {
+    pub fn tween(duration_secs: f32, ease: EaseFunction, lens: impl Lens<T>) -> Self {
+        Self::Tween {
+            duration_secs,
+            ease,
+            lens: Box::new(lens),
+        }
+    }
+
+    pub fn delay(secs: f32) -> Self {
+        Self::Delay(secs)
+    }
+
+    /// Play `next` after this.
+    pub fn then(self, next: Self) -> Self {
+        match self {
+            Self::Sequence(mut steps) => {
+                steps.push(next);
+                Self::Sequence(steps)
+            }
+            first => Self::Sequence(vec![first, next]),
+        }
+    }
+
+    /// Play `other` at the same time as this.
+    pub fn with(self, other: Self) -> Self {
+        match self {
+            Self::Parallel(mut tracks) => {
+                tracks.push(other);
+                Self::Parallel(tracks)
+            }
+            first => Self::Parallel(vec![first, other]),
+        }
+    }
+
+    /// How long it takes to play once, in seconds.
+    pub fn duration_secs(&self) -> f32 {
+        match self {
+            Self::Tween { duration_secs, .. } | Self::Delay(duration_secs) => {
+                duration_secs.max(0.0)
+            }
+            Self::Sequence(steps) => steps.iter().map(Self::duration_secs).sum(),
+            Self::Parallel(tracks) => tracks.iter().map(Self::duration_secs).fold(0.0, f32::max),
+        }
+    }
+
+    /// Apply every tween that was active between `from` and `to` seconds, in the order
+    /// they were passed, so that tweens that were skipped over still reach their end.
+    /// `to` comes before `from` while playing backwards.
+    /// `target` is only dereferenced mutably while a tween is active, so that e.g. a delay
+    /// doesn't mark a component as changed.
+    fn sample(&mut self, target: &mut impl DerefMut<Target = T>, from: f32, to: f32) {
+        match self {
+            Self::Tween {
+                duration_secs,
+                ease,
+                lens,
+            } => {
+                let (low, high) = (from.min(to), from.max(to));
+                if low > *duration_secs || high < 0.0 {
+                    return;
+                }
+                let t = if *duration_secs > 0.0 {
+                    to / *duration_secs
+                } else if to >= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+                lens.lerp(&mut **target, ease.ease(t));
+            }
+            Self::Delay(_) => {}
+            Self::Sequence(steps) => {
+                let mut start = 0.0;
+                let mut offsets = Vec::with_capacity(steps.len());
+                for step in steps.iter() {
+                    offsets.push(start);
+                    start += step.duration_secs();
+                }
+                let mut steps: Vec<_> = steps.iter_mut().zip(offsets).collect();
+                if to < from {
+                    steps.reverse();
+                }
+                for (step, offset) in steps {
+                    step.sample(target, from - offset, to - offset);
+                }
+            }
+            Self::Parallel(tracks) => {
+                for track in tracks {
+                    track.sample(target, from, to);
+                }
+            }
+        }
+    }
+}
+
+/// How many times an [`Animator`] plays its [`Tweenable`].
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    Times(u32),
+    Forever,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Self::Times(1)
+    }
+}
+
+/// Plays a [`Tweenable`] on the entity's `T` component.
+/// Triggers [`TweenCompleted`] and removes itself once it has finished.
+#[derive(Component)]
+pub struct Animator<T: Component> {
+    tweenable: Tweenable<T>,
+    pub repeat: Repeat,
+    /// Play every other repetition backwards.
+    pub yoyo: bool,
+    /// Seconds since the animator started, across all repetitions.
+    elapsed_secs: f32,
+}
+
+impl<T: Component> Animator<T> {
+    pub fn new(tweenable: Tweenable<T>) -> Self {
+        Self {
+            tweenable,
+            repeat: Repeat::default(),
+            yoyo: false,
+            elapsed_secs: 0.0,
+        }
+    }
+
+    pub fn with_repeat(self, repeat: Repeat) -> Self {
+        Self { repeat, ..self }
+    }
+
+    pub fn with_yoyo(self) -> Self {
+        Self { yoyo: true, ..self }
+    }
+
+    /// Which repetition the animator is in at `secs`, and the time within it.
+    fn cycle(&self, secs: f32, duration: f32) -> (u32, f32) {
+        let mut cycle = (secs / duration).floor() as u32;
+        if let Repeat::Times(times) = self.repeat {
+            cycle = cycle.min(times.saturating_sub(1));
+        }
+        let t = secs - cycle as f32 * duration;
+        (cycle, self.directed(cycle, t, duration))
+    }
+
+    /// Flip `t` in repetitions that play backwards.
+    fn directed(&self, cycle: u32, t: f32, duration: f32) -> f32 {
+        if self.yoyo && cycle % 2 == 1 {
+            duration - t
+        } else {
+            t
+        }
+    }
+
+    /// Advance by `delta` seconds and apply the tweens to `target`.
+    /// Returns whether the animator has finished.
+    fn advance(&mut self, target: &mut impl DerefMut<Target = T>, delta: f32) -> bool {
+        let duration = self.tweenable.duration_secs();
+        let total = match self.repeat {
+            Repeat::Times(times) => times as f32 * duration,
+            Repeat::Forever => f32::INFINITY,
+        };
+        let previous = self.elapsed_secs;
+        self.elapsed_secs = (previous + delta).min(total);
+        if duration <= 0.0 {
+            self.tweenable.sample(target, 0.0, 0.0);
+            return true;
+        }
+
+        let (previous_cycle, from) = self.cycle(previous, duration);
+        let (cycle, to) = self.cycle(self.elapsed_secs, duration);
+        if cycle != previous_cycle {
+            // Finish the previous repetition before starting the next one.
+            let end = self.directed(previous_cycle, duration, duration);
+            self.tweenable.sample(target, from, end);
+            let start = self.directed(cycle, 0.0, duration);
+            self.tweenable.sample(target, start, to);
+        } else {
+            self.tweenable.sample(target, from, to);
+        }
+        self.elapsed_secs >= total
+    }
+}
+
+fn advance_animators<T: Component>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut animator_query: Query<(Entity, &mut Animator<T>, &mut T)>,
+) {
+    for (entity, mut animator, mut target) in &mut animator_query {
+        if animator.advance(&mut target, time.delta_seconds()) {
+            commands.entity(entity).remove::<Animator<T>>();
+            commands.trigger_targets(TweenCompleted, entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::component::Tick;
+
+    use super::{
+        lens::{TransformPositionLens, TransformScaleLens},
+        *,
+    };
+
+    fn move_x(start: f32, end: f32, duration_secs: f32) -> Tweenable<Transform> {
+        Tweenable::tween(
+            duration_secs,
+            EaseFunction::Linear,
+            TransformPositionLens {
+                start: Vec3::X * start,
+                end: Vec3::X * end,
+            },
+        )
+    }
+
+    fn scale(start: f32, end: f32, duration_secs: f32) -> Tweenable<Transform> {
+        Tweenable::tween(
+            duration_secs,
+            EaseFunction::Linear,
+            TransformScaleLens {
+                start: Vec3::splat(start),
+                end: Vec3::splat(end),
+            },
+        )
+    }
+
+    /// Advance `animator` by `delta` seconds, returning whether it finished.
+    fn advance(animator: &mut Animator<Transform>, transform: &mut Transform, delta: f32) -> bool {
+        animator.advance(&mut &mut *transform, delta)
+    }
+
+    #[test]
+    fn sequence_finishes_skipped_steps() {
+        let mut animator = Animator::new(
+            move_x(0.0, 10.0, 1.0)
+                .then(Tweenable::delay(1.0))
+                .then(scale(1.0, 2.0, 1.0)),
+        );
+        let mut transform = Transform::default();
+        assert!(!advance(&mut animator, &mut transform, 0.5));
+        assert_eq!(transform.translation.x, 5.0);
+
+        // Jump past the end of the sequence in one step.
+        assert!(advance(&mut animator, &mut transform, 10.0));
+        assert_eq!(transform.translation.x, 10.0);
+        assert_eq!(transform.scale, Vec3::splat(2.0));
+    }
+
+    #[test]
+    fn parallel_plays_until_the_longest_track_ends() {
+        let tweenable = move_x(0.0, 10.0, 1.0).with(scale(1.0, 3.0, 2.0));
+        assert_eq!(tweenable.duration_secs(), 2.0);
+
+        let mut animator = Animator::new(tweenable);
+        let mut transform = Transform::default();
+        assert!(!advance(&mut animator, &mut transform, 1.0));
+        assert_eq!(transform.translation.x, 10.0);
+        assert_eq!(transform.scale, Vec3::splat(2.0));
+        assert!(advance(&mut animator, &mut transform, 1.0));
+        assert_eq!(transform.scale, Vec3::splat(3.0));
+    }
+
+    #[test]
+    fn yoyo_plays_every_other_repetition_backwards() {
+        let mut animator = Animator::new(move_x(0.0, 10.0, 1.0))
+            .with_repeat(Repeat::Times(2))
+            .with_yoyo();
+        let mut transform = Transform::default();
+        assert!(!advance(&mut animator, &mut transform, 0.5));
+        assert_eq!(transform.translation.x, 5.0);
+        assert!(!advance(&mut animator, &mut transform, 0.75));
+        assert_eq!(transform.translation.x, 7.5);
+        assert!(advance(&mut animator, &mut transform, 1.0));
+        assert_eq!(transform.translation.x, 0.0);
+    }
+
+    #[test]
+    fn repeat_times_finishes_after_the_last_repetition() {
+        let mut animator = Animator::new(move_x(0.0, 10.0, 1.0)).with_repeat(Repeat::Times(3));
+        assert_eq!(animator.cycle(2.5, 1.0), (2, 0.5));
+        // Time past the end stays in the last repetition.
+        assert_eq!(animator.cycle(5.0, 1.0), (2, 3.0));
+
+        let mut transform = Transform::default();
+        assert!(!advance(&mut animator, &mut transform, 2.5));
+        assert_eq!(transform.translation.x, 5.0);
+        assert!(advance(&mut animator, &mut transform, 1.0));
+        assert_eq!(transform.translation.x, 10.0);
+    }
+
+    #[test]
+    fn repeat_forever_never_finishes() {
+        let mut animator = Animator::new(move_x(0.0, 10.0, 1.0)).with_repeat(Repeat::Forever);
+        let mut transform = Transform::default();
+        assert!(!advance(&mut animator, &mut transform, 100.25));
+        assert_eq!(transform.translation.x, 2.5);
+    }
+
+    #[test]
+    fn delay_does_not_mark_the_target_changed() {
+        let mut animator = Animator::new(Tweenable::delay(1.0).then(move_x(0.0, 10.0, 1.0)));
+        let mut transform = Transform::default();
+        let (mut added, mut changed) = (Tick::new(0), Tick::new(0));
+        let mut target = Mut::new(
+            &mut transform,
+            &mut added,
+            &mut changed,
+            Tick::new(1),
+            Tick::new(2),
+        );
+        animator.advance(&mut target, 0.5);
+        assert!(!target.is_changed());
+        animator.advance(&mut target, 1.0);
+        assert!(target.is_changed());
+    }
+}